@@ -216,7 +216,8 @@ fn main() {
                 }
 
                 if let Some(handle) = s[bone].desired_end() {
-                    let handle = s[bone].start() + (handle + s[bone].entry_angle());
+                    let handle =
+                        s[bone].start() + s.to_world_offset(handle + s[bone].entry_angle());
                     let handle = handle.to_vec::<Point<f32>>().map(|d| scale * d);
                     context.gfx.draw_shape(
                         Shape::filled_circle(Px::new(3), Color::WHITE, Origin::Center)
@@ -1,17 +1,32 @@
 #![allow(missing_docs)]
-use std::{
-    borrow::Cow,
+use alloc::{
+    borrow::{Cow, ToOwned},
+    collections::{BTreeMap, BTreeSet},
+    string::String,
+    sync::Arc,
+    vec::Vec,
+};
+use core::{
     cmp::Ordering,
-    collections::HashMap,
+    fmt::Display,
     num::TryFromIntError,
     ops::{Add, Deref, DerefMut, Sub},
-    sync::Arc,
     time::Duration,
 };
 
-use easing_function::easings::StandardEasing;
+use easing_function::{Easing, EasingFunction};
+
+use crate::{Bone, BoneId, Coordinate, IdMap, Joint, JointId, Rotation, Scalar, Skeleton, Vector};
 
-use crate::{Rotation, Bone, BoneId, Coordinate, Joint, JointId, Skeleton, Vector};
+mod blend_space;
+mod easing;
+mod state_machine;
+
+pub use blend_space::{BlendSpace1D, BlendSpace2D, RunningBlendSpace};
+pub use easing::{CubicBezier, KeyframeEasing, StepPosition, Steps};
+pub use state_machine::{
+    Comparison, Condition, RunningStateMachine, State, StateId, StateMachine, Transition,
+};
 
 #[derive(Default, Debug, PartialEq, Clone)]
 pub struct Animation(Arc<AnimationData>);
@@ -25,6 +40,27 @@ impl Animation {
         self.data_mut().timelines.push(timeline);
     }
 
+    pub fn push_event_track(&mut self, track: EventTrack) -> usize {
+        let events = &mut self.data_mut().events;
+        events.push(track);
+        events.len() - 1
+    }
+
+    #[must_use]
+    pub fn with_event_track(mut self, track: EventTrack) -> Self {
+        self.push_event_track(track);
+        self
+    }
+
+    #[must_use]
+    pub fn event_tracks(&self) -> &[EventTrack] {
+        &self.0.events
+    }
+
+    pub fn event_track_mut(&mut self, index: usize) -> Option<&mut EventTrack> {
+        self.data_mut().events.get_mut(index)
+    }
+
     #[must_use]
     pub fn with(mut self, timeline: Timeline) -> Self {
         self.push(timeline);
@@ -54,7 +90,7 @@ impl Animation {
                 if let Some(var) = self.data_mut().variables.get_mut(name) {
                     *var = value;
                 } else {
-                    self.data_mut().variables.insert(name.to_string(), value);
+                    self.data_mut().variables.insert(name.to_owned(), value);
                 }
             }
         }
@@ -65,17 +101,188 @@ impl Animation {
         self.0.variables.get(name).copied()
     }
 
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        let keyframes = self
+            .0
+            .timelines
+            .iter()
+            .filter_map(|timeline| timeline.frames.last())
+            .map(|frame| frame.frame_offset);
+        let events = self
+            .0
+            .events
+            .iter()
+            .filter_map(|track| track.events.last())
+            .map(|event| event.frame_offset);
+        keyframes
+            .chain(events)
+            .max()
+            .map(Duration::from)
+            .unwrap_or_default()
+    }
+
+    #[must_use]
+    pub fn rest_pose(&self, skeleton: &Skeleton) -> Pose {
+        let mut pose = Pose::new();
+        for timeline in self.iter() {
+            pose.set(timeline.target.clone(), timeline.target.get(skeleton));
+        }
+        pose
+    }
+
+    #[must_use]
+    pub fn sample(&self, time: Duration, rest_pose: &Pose) -> Pose {
+        let mut pose = Pose::new();
+        for timeline in self.iter().filter(|timeline| !timeline.frames.is_empty()) {
+            let start_value = rest_pose.get(&timeline.target).unwrap_or(Value::Invalid);
+            pose.set(
+                timeline.target.clone(),
+                timeline.value_at(time, start_value),
+            );
+        }
+        pose
+    }
+
+    #[must_use]
+    pub fn remapped(&self, ids: &IdMap) -> Self {
+        Self(Arc::new(AnimationData {
+            variables: self.0.variables.clone(),
+            events: self.0.events.clone(),
+            timelines: self
+                .0
+                .timelines
+                .iter()
+                .filter_map(|timeline| {
+                    Some(Timeline {
+                        target: timeline.target.remapped(ids)?,
+                        frames: timeline.frames.clone(),
+                    })
+                })
+                .collect(),
+        }))
+    }
+
+    pub fn retargeted(&self, from: &Skeleton, to: &Skeleton) -> Result<Self, Vec<UnmatchedTarget>> {
+        let mut unmatched = Vec::new();
+        let mut timelines = Vec::with_capacity(self.0.timelines.len());
+        for (index, timeline) in self.0.timelines.iter().enumerate() {
+            let Some((target, ratio)) = timeline.target.retargeted(from, to) else {
+                unmatched.push(UnmatchedTarget {
+                    timeline: index,
+                    target: timeline.target.clone(),
+                });
+                continue;
+            };
+            let scale_targets = matches!(
+                target,
+                Target::Bone {
+                    property: BoneProperty::Target,
+                    ..
+                } | Target::LabeledBone {
+                    property: BoneProperty::Target,
+                    ..
+                }
+            );
+            timelines.push(Timeline {
+                target,
+                frames: timeline
+                    .frames
+                    .iter()
+                    .map(|frame| {
+                        let mut frame = frame.clone();
+                        if scale_targets {
+                            frame.update = frame.update.scaled(ratio);
+                        }
+                        frame
+                    })
+                    .collect(),
+            });
+        }
+
+        if unmatched.is_empty() {
+            Ok(Self(Arc::new(AnimationData {
+                timelines,
+                variables: self.0.variables.clone(),
+                events: self.0.events.clone(),
+            })))
+        } else {
+            Err(unmatched)
+        }
+    }
+
+    pub fn resolve(&self, skeleton: &Skeleton) -> Result<Self, Vec<UnresolvedLabel>> {
+        let mut errors = Vec::new();
+        let mut timelines = Vec::with_capacity(self.0.timelines.len());
+        for (index, timeline) in self.0.timelines.iter().enumerate() {
+            match timeline.target.resolve(skeleton) {
+                Ok(target) => timelines.push(Timeline {
+                    target,
+                    frames: timeline.frames.clone(),
+                }),
+                Err(label) => errors.push(UnresolvedLabel {
+                    timeline: index,
+                    label,
+                }),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(Self(Arc::new(AnimationData {
+                variables: self.0.variables.clone(),
+                timelines,
+                events: self.0.events.clone(),
+            })))
+        } else {
+            Err(errors)
+        }
+    }
+
+    pub fn try_start(&self, skeleton: &Skeleton) -> Result<RunningAnimation, Vec<UnresolvedLabel>> {
+        self.resolve(skeleton).map(|animation| animation.start())
+    }
+
     #[must_use]
     pub fn start(&self) -> RunningAnimation {
         RunningAnimation {
             animation: self.clone(),
-            repeat: false,
-            elapsed: Duration::ZERO,
-            timelines: Vec::new(),
+            time: Duration::ZERO,
+            rate: 1.,
+            looping: Looping::Once,
+            repeat_count: None,
+            cycles: 0,
+            reversed: false,
+            started: false,
+            finished: false,
+            rest: None,
+            crossed: Vec::new(),
         }
     }
 }
 
+/// Serializing an animation fails if any of its keyframes uses a
+/// [`KeyframeEasing::Custom`] easing, as custom easing functions can't be
+/// serialized.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Animation {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Animation {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        AnimationData::deserialize(deserializer).map(|data| Self(Arc::new(data)))
+    }
+}
+
 impl Deref for Animation {
     type Target = [Timeline];
 
@@ -94,8 +301,10 @@ impl DerefMut for Animation {
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", serde(rename = "Animation"))]
 struct AnimationData {
-    variables: HashMap<String, f32>,
+    variables: BTreeMap<String, f32>,
     timelines: Vec<Timeline>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    events: Vec<EventTrack>,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -114,6 +323,11 @@ impl Timeline {
         }
     }
 
+    #[must_use]
+    pub const fn target(&self) -> &Target {
+        &self.target
+    }
+
     #[must_use]
     pub fn with_frame(mut self, frame: Keyframe) -> Self {
         self.insert_frame(frame);
@@ -134,6 +348,43 @@ impl Timeline {
         }
     }
 
+    fn value_at(&self, time: Duration, start_value: Value) -> Value {
+        let mut points = Vec::with_capacity(self.frames.len() + 1);
+        let mut value = start_value;
+        points.push((0., value));
+        for keyframe in &self.frames {
+            value = keyframe.update.target(value);
+            points.push((Duration::from(keyframe.frame_offset).as_secs_f32(), value));
+        }
+
+        let time = time.as_secs_f32();
+        let Some(index) = points[1..].iter().position(|(offset, _)| time < *offset) else {
+            return value;
+        };
+        let keyframe = &self.frames[index];
+        let (start, end) = (points[index], points[index + 1]);
+        let seconds = end.0 - start.0;
+        let percent = keyframe.easing.ease((time - start.0) / seconds);
+
+        // The segment's start is either the timeline's entry or the previous
+        // keyframe.
+        let start_tangent = index
+            .checked_sub(1)
+            .map_or(Tangent::Linear, |previous| self.frames[previous].tangent);
+        if start_tangent == Tangent::Linear && keyframe.tangent == Tangent::Linear {
+            return start.1.lerp(end.1, percent);
+        }
+
+        let previous = index
+            .checked_sub(1)
+            .map(|previous| points[previous])
+            .filter(|previous| previous.0 < start.0);
+        let next = points.get(index + 2).copied().filter(|next| next.0 > end.0);
+        let (_, outgoing) = start_tangent.slopes(previous, start, Some(end));
+        let (incoming, _) = keyframe.tangent.slopes(Some(start), end, next);
+        start.1.hermite(outgoing, end.1, incoming, seconds, percent)
+    }
+
     pub fn set_frame_offset(&mut self, index: usize, new_offset: Frame) {
         let current_offset = self.frames[index].frame_offset;
         let (slice_offset, slice) = match current_offset.cmp(&new_offset) {
@@ -145,7 +396,8 @@ impl Timeline {
         match slice.binary_search_by_key(&new_offset, |f| f.frame_offset) {
             Ok(relative_index) => {
                 let new_index = relative_index + slice_offset;
-                self.frames[new_index].easing = self.frames[index].easing;
+                self.frames[new_index].easing = self.frames[index].easing.clone();
+                self.frames[new_index].tangent = self.frames[index].tangent;
                 self.frames[new_index].update = self.frames[index].update;
                 self.frames.remove(index);
             }
@@ -176,12 +428,116 @@ impl DerefMut for Timeline {
     }
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Default, Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EventTrack {
+    pub name: String,
+    events: Vec<Event>,
+}
+
+impl EventTrack {
+    #[must_use]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            events: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_event(mut self, event: Event) -> Self {
+        self.insert_event(event);
+        self
+    }
+
+    pub fn insert_event(&mut self, event: Event) {
+        let index = self
+            .events
+            .partition_point(|existing| existing.frame_offset <= event.frame_offset);
+        self.events.insert(index, event);
+    }
+
+    pub fn remove_event(&mut self, index: usize) -> Event {
+        self.events.remove(index)
+    }
+
+    fn crossed(&self, range: FrameRange) -> impl Iterator<Item = &Event> + '_ {
+        let start = self.events.partition_point(|event| {
+            !range.contains(event.frame_offset) && event.frame_offset <= range.start
+        });
+        self.events[start..]
+            .iter()
+            .take_while(move |event| range.contains(event.frame_offset))
+    }
+}
+
+impl Deref for EventTrack {
+    type Target = [Event];
+
+    fn deref(&self) -> &Self::Target {
+        &self.events
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Event {
+    frame_offset: Frame,
+    pub name: String,
+    pub payload: Option<Value>,
+}
+
+impl Event {
+    #[must_use]
+    pub fn new(frame_offset: Frame, name: impl Into<String>) -> Self {
+        Self {
+            frame_offset,
+            name: name.into(),
+            payload: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_payload(mut self, payload: impl Into<Value>) -> Self {
+        self.payload = Some(payload.into());
+        self
+    }
+
+    #[must_use]
+    pub const fn frame_offset(&self) -> Frame {
+        self.frame_offset
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct CrossedEvent {
+    pub track: usize,
+    pub event: Event,
+}
+
+#[derive(Clone, Copy)]
+struct FrameRange {
+    start: Frame,
+    end: Frame,
+    inclusive_start: bool,
+    inclusive_end: bool,
+}
+
+impl FrameRange {
+    fn contains(self, frame: Frame) -> bool {
+        (frame > self.start || (self.inclusive_start && frame == self.start))
+            && (frame < self.end || (self.inclusive_end && frame == self.end))
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Keyframe {
     frame_offset: Frame,
     pub update: PropertyUpdate,
-    pub easing: StandardEasing,
+    pub easing: KeyframeEasing,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tangent: Tangent,
 }
 
 impl Keyframe {
@@ -190,18 +546,74 @@ impl Keyframe {
         Self {
             frame_offset,
             update,
-            easing: StandardEasing::default(),
+            easing: KeyframeEasing::default(),
+            tangent: Tangent::default(),
         }
     }
 
     #[must_use]
-    pub fn with_easing(mut self, easing: StandardEasing) -> Self {
-        self.easing = easing;
+    pub fn with_easing(mut self, easing: impl Into<KeyframeEasing>) -> Self {
+        self.easing = easing.into();
+        self
+    }
+
+    #[must_use]
+    pub fn with_tangent(mut self, tangent: Tangent) -> Self {
+        self.tangent = tangent;
         self
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+/// Controls the slope of a timeline's curve as it passes through a keyframe.
+/// Slopes are measured in change per second.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Tangent {
+    /// Each side of the keyframe is a straight line to its neighbor.
+    #[default]
+    Linear,
+    /// A Catmull-Rom tangent, smoothly passing through the keyframe.
+    Auto,
+    /// The curve levels out at the keyframe.
+    Flat,
+    /// Independent incoming and outgoing slopes.
+    Broken { incoming: Value, outgoing: Value },
+}
+
+impl Tangent {
+    fn slopes(
+        self,
+        previous: Option<(f32, Value)>,
+        point: (f32, Value),
+        next: Option<(f32, Value)>,
+    ) -> (Value, Value) {
+        let slope = |(from_time, from): (f32, Value), (to_time, to): (f32, Value)| {
+            from.slope(to, to_time - from_time)
+        };
+        let incoming = previous.map(|previous| slope(previous, point));
+        let outgoing = next.map(|next| slope(point, next));
+        match self {
+            Tangent::Linear => {
+                let zero = point.1.zeroed();
+                (
+                    incoming.or(outgoing).unwrap_or(zero),
+                    outgoing.or(incoming).unwrap_or(zero),
+                )
+            }
+            Tangent::Auto => match (previous, next) {
+                (Some(previous), Some(next)) => {
+                    let slope = slope(previous, next);
+                    (slope, slope)
+                }
+                _ => Tangent::Linear.slopes(previous, point, next),
+            },
+            Tangent::Flat => (point.1.zeroed(), point.1.zeroed()),
+            Tangent::Broken { incoming, outgoing } => (incoming, outgoing),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Target {
     Bone {
@@ -212,20 +624,131 @@ pub enum Target {
         joint: JointId,
         property: JointProperty,
     },
+    Skeleton {
+        property: SkeletonProperty,
+    },
+    LabeledBone {
+        label: String,
+        property: BoneProperty,
+    },
+    LabeledJoint {
+        label: String,
+        property: JointProperty,
+    },
 }
 
 impl Target {
     #[must_use]
     pub fn get(&self, skeleton: &Skeleton) -> Value {
         match self {
-            Target::Bone { bone, property } => skeleton.bone(*bone).map(|bone| property.get(bone)),
+            Target::Bone { bone, property } => skeleton
+                .bone(*bone)
+                .map(|bone| property.get_in_skeleton(bone, skeleton)),
             Target::Joint { joint, property } => {
                 skeleton.joint(*joint).map(|joint| property.get(joint))
             }
+            Target::Skeleton { property } => Some(property.get(skeleton)),
+            Target::LabeledBone { label, property } => skeleton
+                .find_bone_by_label(label)
+                .map(|bone| property.get_in_skeleton(&skeleton[bone], skeleton)),
+            Target::LabeledJoint { label, property } => skeleton
+                .find_joint_by_label(label)
+                .map(|joint| property.get(&skeleton[joint])),
         }
         .unwrap_or(Value::Invalid)
     }
 
+    #[must_use]
+    pub fn retargeted(&self, from: &Skeleton, to: &Skeleton) -> Option<(Self, f32)> {
+        fn length_ratio(from: &Bone, to: &Bone) -> f32 {
+            let from = from.kind().full_length();
+            if from > 0. {
+                to.kind().full_length() / from
+            } else {
+                1.
+            }
+        }
+
+        match self {
+            Target::Bone { bone, property } => {
+                let source = from.bone(*bone)?;
+                if source.label().is_empty() {
+                    return None;
+                }
+                let bone = to.find_bone_by_label(source.label())?;
+                Some((
+                    Target::Bone {
+                        bone,
+                        property: property.clone(),
+                    },
+                    length_ratio(source, &to[bone]),
+                ))
+            }
+            Target::Joint { joint, property } => {
+                let source = from.joint(*joint)?;
+                if source.label().is_empty() {
+                    return None;
+                }
+                Some((
+                    Target::Joint {
+                        joint: to.find_joint_by_label(source.label())?,
+                        property: property.clone(),
+                    },
+                    1.,
+                ))
+            }
+            Target::LabeledBone { label, .. } => {
+                let source = from.find_bone_by_label(label)?;
+                let bone = to.find_bone_by_label(label)?;
+                Some((self.clone(), length_ratio(&from[source], &to[bone])))
+            }
+            Target::LabeledJoint { label, .. } => {
+                to.find_joint_by_label(label)?;
+                Some((self.clone(), 1.))
+            }
+            Target::Skeleton { .. } => Some((self.clone(), 1.)),
+        }
+    }
+
+    pub fn resolve(&self, skeleton: &Skeleton) -> Result<Self, Label> {
+        match self {
+            Target::LabeledBone { label, property } => skeleton
+                .find_bone_by_label(label)
+                .map(|bone| Target::Bone {
+                    bone,
+                    property: property.clone(),
+                })
+                .ok_or_else(|| Label::Bone(label.clone())),
+            Target::LabeledJoint { label, property } => skeleton
+                .find_joint_by_label(label)
+                .map(|joint| Target::Joint {
+                    joint,
+                    property: property.clone(),
+                })
+                .ok_or_else(|| Label::Joint(label.clone())),
+            Target::Bone { .. } | Target::Joint { .. } | Target::Skeleton { .. } => {
+                Ok(self.clone())
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn remapped(&self, ids: &IdMap) -> Option<Self> {
+        match self {
+            Target::Bone { bone, property } => Some(Target::Bone {
+                bone: ids.bone(*bone)?,
+                property: property.clone(),
+            }),
+            Target::Joint { joint, property } => Some(Target::Joint {
+                joint: ids.joint(*joint)?,
+                property: property.clone(),
+            }),
+            Target::Skeleton { .. } | Target::LabeledBone { .. } | Target::LabeledJoint { .. } => {
+                None
+            }
+        }
+    }
+
     pub fn update(&self, value: Value, skeleton: &mut Skeleton) {
         match self {
             Target::Bone { bone, property } => {
@@ -240,19 +763,85 @@ impl Target {
                 };
                 property.update(value, joint);
             }
+            Target::Skeleton { property } => property.update(value, skeleton),
+            Target::LabeledBone { label, property } => {
+                let Some(bone) = skeleton.find_bone_by_label(label) else {
+                    return;
+                };
+                property.update(value, &mut skeleton[bone]);
+            }
+            Target::LabeledJoint { label, property } => {
+                let Some(joint) = skeleton.find_joint_by_label(label) else {
+                    return;
+                };
+                property.update(value, &mut skeleton[joint]);
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Label {
+    Bone(String),
+    Joint(String),
+}
+
+impl Display for Label {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Label::Bone(label) => write!(f, "bone \"{label}\""),
+            Label::Joint(label) => write!(f, "joint \"{label}\""),
         }
     }
 }
 
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct UnresolvedLabel {
+    pub timeline: usize,
+    pub label: Label,
+}
+
+impl Display for UnresolvedLabel {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "timeline {}: no {} found", self.timeline, self.label)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UnresolvedLabel {}
+
 #[derive(Debug, PartialEq, Clone)]
+pub struct UnmatchedTarget {
+    pub timeline: usize,
+    pub target: Target,
+}
+
+impl Display for UnmatchedTarget {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "timeline {}: no match found for {:?}",
+            self.timeline, self.target
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UnmatchedTarget {}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BoneProperty {
     Target,
     // Scale,
     Inverse,
+    Pole,
 }
 
 impl BoneProperty {
+    /// Returns the property's value. A bone without a pole returns a zero
+    /// vector for [`BoneProperty::Pole`]; use
+    /// [`get_in_skeleton`](Self::get_in_skeleton) to get its solved pole.
     #[must_use]
     pub fn get(&self, bone: &Bone) -> Value {
         match self {
@@ -262,6 +851,21 @@ impl BoneProperty {
             ),
             // BoneProperty::Scale => ,
             BoneProperty::Inverse => Value::Bool(bone.kind().is_inverse()),
+            BoneProperty::Pole => Value::Vector(bone.pole().unwrap_or_default()),
+        }
+    }
+
+    /// Returns the property's value. A bone without a pole returns the
+    /// direction its joint was solved in for [`BoneProperty::Pole`].
+    #[must_use]
+    pub fn get_in_skeleton(&self, bone: &Bone, skeleton: &Skeleton) -> Value {
+        match (self, bone.pole()) {
+            (BoneProperty::Pole, None) => {
+                Value::Vector(bone.solved_joint().map_or_else(Vector::default, |joint| {
+                    skeleton.to_local_vector(joint - bone.start()) - bone.entry_angle()
+                }))
+            }
+            _ => self.get(bone),
         }
     }
 
@@ -279,11 +883,17 @@ impl BoneProperty {
                 };
                 bone.kind_mut().set_inverse(value);
             }
+            BoneProperty::Pole => {
+                let Value::Vector(value) = value else {
+                    return;
+                };
+                bone.set_pole(Some(value));
+            }
         }
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum JointProperty {
     Angle,
@@ -309,6 +919,48 @@ impl JointProperty {
     }
 }
 
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SkeletonProperty {
+    Scale,
+    FlipHorizontal,
+    FlipVertical,
+}
+
+impl SkeletonProperty {
+    #[must_use]
+    pub fn get(&self, skeleton: &Skeleton) -> Value {
+        match self {
+            SkeletonProperty::Scale => Value::Number(skeleton.scale()),
+            SkeletonProperty::FlipHorizontal => Value::Bool(skeleton.flip_horizontal()),
+            SkeletonProperty::FlipVertical => Value::Bool(skeleton.flip_vertical()),
+        }
+    }
+
+    pub fn update(&self, value: Value, skeleton: &mut Skeleton) {
+        match self {
+            SkeletonProperty::Scale => {
+                let Value::Number(value) = value else {
+                    return;
+                };
+                skeleton.set_scale(value);
+            }
+            SkeletonProperty::FlipHorizontal => {
+                let Value::Bool(value) = value else {
+                    return;
+                };
+                skeleton.set_flip_horizontal(value);
+            }
+            SkeletonProperty::FlipVertical => {
+                let Value::Bool(value) = value else {
+                    return;
+                };
+                skeleton.set_flip_vertical(value);
+            }
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone, Copy)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Value {
@@ -318,6 +970,17 @@ pub enum Value {
     Bool(bool),
 }
 
+impl Value {
+    fn zeroed(self) -> Self {
+        match self {
+            Value::Invalid => Value::Invalid,
+            Value::Number(_) => Value::Number(0.),
+            Value::Vector(_) => Value::Vector(Vector::default()),
+            Value::Bool(_) => Value::Bool(false),
+        }
+    }
+}
+
 impl From<f32> for Value {
     fn from(value: f32) -> Self {
         Self::Number(value)
@@ -379,71 +1042,751 @@ impl PropertyUpdate {
             PropertyUpdate::Add(delta) => initial + *delta,
         }
     }
+
+    fn scaled(self, ratio: f32) -> Self {
+        fn scale(value: Value, ratio: f32) -> Value {
+            match value {
+                Value::Vector(vector) => {
+                    Value::Vector(Vector::new(vector.magnitude * ratio, vector.direction))
+                }
+                other => other,
+            }
+        }
+
+        match self {
+            PropertyUpdate::ChangeTo(value) => PropertyUpdate::ChangeTo(scale(value, ratio)),
+            PropertyUpdate::Add(value) => PropertyUpdate::Add(scale(value, ratio)),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Looping {
+    #[default]
+    Once,
+    Repeat,
+    PingPong,
 }
 
 pub struct RunningAnimation {
     animation: Animation,
-    elapsed: Duration,
-    repeat: bool,
-    timelines: Vec<RunningTimeline>,
+    time: Duration,
+    rate: f32,
+    looping: Looping,
+    repeat_count: Option<u32>,
+    cycles: u32,
+    reversed: bool,
+    started: bool,
+    finished: bool,
+    rest: Option<Pose>,
+    crossed: Vec<CrossedEvent>,
 }
 
 impl RunningAnimation {
     #[must_use]
     pub fn looping(mut self) -> Self {
-        self.repeat = true;
+        self.looping = Looping::Repeat;
         self
     }
 
-    pub fn update(&mut self, elapsed: Duration, skeleton: &mut Skeleton) -> bool {
-        if self.animation.is_empty() {
-            return false;
-        }
-
-        if self.timelines.is_empty() {
-            self.timelines = self
-                .animation
-                .iter()
-                .map(|timeline| {
-                    let frame_start_value = timeline.target.get(skeleton);
-                    RunningTimeline {
-                        frame_entry: Frame::MIN,
-                        frame: 0,
-                        frame_start_value,
-                        frame_target_value: timeline
-                            .frames
-                            .first()
-                            .map_or(Value::Invalid, |f| f.update.target(frame_start_value)),
-                    }
-                })
-                .collect();
+    #[must_use]
+    pub fn ping_pong(mut self) -> Self {
+        self.looping = Looping::PingPong;
+        self
+    }
+
+    #[must_use]
+    pub fn with_looping(mut self, looping: Looping) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    /// Limits looping to `count` cycles. When ping-ponging, a cycle is a full
+    /// trip forward and back.
+    #[must_use]
+    pub fn with_repeat_count(mut self, count: u32) -> Self {
+        self.repeat_count = Some(count);
+        self
+    }
+
+    #[must_use]
+    pub fn with_rate(mut self, rate: f32) -> Self {
+        self.set_rate(rate);
+        self
+    }
+
+    /// Sets the playback rate. Negative rates play in reverse, and an
+    /// animation that hasn't been updated or seeked yet will start from its
+    /// end. Rates that aren't finite are ignored.
+    pub fn set_rate(&mut self, rate: f32) {
+        if !rate.is_finite() {
+            return;
         }
+        if !self.started && self.time == Duration::ZERO && rate < 0. {
+            self.time = self.animation.duration();
+        }
+        self.rate = rate;
+        if self.rate != 0. {
+            self.finished = false;
+        }
+    }
+
+    #[must_use]
+    pub const fn rate(&self) -> f32 {
+        self.rate
+    }
+
+    #[must_use]
+    pub const fn time(&self) -> Duration {
+        self.time
+    }
+
+    /// Moves playback to `time` without crossing any events. Seeking restarts
+    /// the repeat count and the ping-pong direction.
+    pub fn seek(&mut self, time: Duration) {
+        self.time = time.min(self.animation.duration());
+        self.cycles = 0;
+        self.reversed = false;
+        self.finished = false;
+    }
 
-        self.elapsed += elapsed;
-        let frame = Frame::try_from(self.elapsed).unwrap_or(Frame::MAX);
+    #[must_use]
+    pub fn crossed_events(&self) -> &[CrossedEvent] {
+        &self.crossed
+    }
+
+    pub fn update(&mut self, elapsed: Duration, skeleton: &mut Skeleton) -> bool {
+        self.advance(elapsed, skeleton)
+    }
+
+    pub fn evaluate(&mut self, elapsed: Duration, skeleton: &Skeleton, pose: &mut Pose) -> bool {
+        self.advance(
+            elapsed,
+            &mut PoseWriter {
+                skeleton,
+                pose,
+                additive: false,
+            },
+        )
+    }
+
+    pub fn evaluate_additive(
+        &mut self,
+        elapsed: Duration,
+        skeleton: &Skeleton,
+        pose: &mut Pose,
+    ) -> bool {
+        self.advance(
+            elapsed,
+            &mut PoseWriter {
+                skeleton,
+                pose,
+                additive: true,
+            },
+        )
+    }
+
+    fn advance(&mut self, elapsed: Duration, output: &mut impl AnimationOutput) -> bool {
+        self.crossed.clear();
+        if self.animation.is_empty() && self.animation.event_tracks().is_empty() {
+            return false;
+        }
+
+        if !self.finished {
+            let scaled = elapsed.as_secs_f64() * f64::from(self.rate.abs());
+            self.play(Duration::try_from_secs_f64(scaled).unwrap_or(Duration::MAX));
+        }
+
+        let rest = self.rest.get_or_insert_with(|| {
+            let mut rest = Pose::new();
+            for timeline in self.animation.iter() {
+                rest.set(timeline.target.clone(), output.get(&timeline.target));
+            }
+            rest
+        });
+        for (target, value) in self.animation.sample(self.time, rest).iter() {
+            output.set(target, value);
+        }
+
+        self.finished
+    }
+
+    fn play(&mut self, mut remaining: Duration) {
+        let duration = self.animation.duration();
+        let mut inclusive_start = !self.started;
+        self.started = true;
         loop {
-            let mut still_running = false;
-            let mut remaining = frame;
-            for (index, timeline) in self.timelines.iter_mut().enumerate() {
-                match timeline.update(&self.animation[index], frame, skeleton) {
-                    Ok(_) => {
-                        still_running = true;
-                    }
-                    Err(unused) => {
-                        remaining = remaining.min(unused);
-                    }
+            let forward = (self.rate >= 0.) != self.reversed;
+            let (boundary, distance) = if forward {
+                (duration, duration.saturating_sub(self.time))
+            } else {
+                (Duration::ZERO, self.time)
+            };
+            if remaining < distance {
+                let start = self.time;
+                self.time = if forward {
+                    self.time + remaining
+                } else {
+                    self.time.saturating_sub(remaining)
+                };
+                self.cross_events(start, self.time, inclusive_start);
+                return;
+            }
+
+            self.cross_events(self.time, boundary, inclusive_start);
+            remaining -= distance;
+            self.time = boundary;
+            if self.looping == Looping::Once
+                || duration == Duration::ZERO
+                || self.repeat_count.is_some_and(|count| {
+                    // Each bounce of a ping-pong is half of a cycle.
+                    let boundaries = if self.looping == Looping::PingPong {
+                        count.saturating_mul(2)
+                    } else {
+                        count
+                    };
+                    self.cycles.saturating_add(1) >= boundaries
+                })
+            {
+                self.finished = true;
+                return;
+            }
+
+            self.cycles += 1;
+            match self.looping {
+                Looping::Once => unreachable!("checked above"),
+                Looping::Repeat => {
+                    self.time = if forward { Duration::ZERO } else { duration };
+                    inclusive_start = true;
+                }
+                Looping::PingPong => {
+                    self.reversed = !self.reversed;
+                    inclusive_start = false;
                 }
             }
+        }
+    }
+
+    fn cross_events(&mut self, from: Duration, to: Duration, inclusive_from: bool) {
+        let from = Frame::try_from(from).unwrap_or(Frame::MAX);
+        let to = Frame::try_from(to).unwrap_or(Frame::MAX);
+        let range = if from <= to {
+            FrameRange {
+                start: from,
+                end: to,
+                inclusive_start: inclusive_from,
+                inclusive_end: true,
+            }
+        } else {
+            FrameRange {
+                start: to,
+                end: from,
+                inclusive_start: true,
+                inclusive_end: inclusive_from,
+            }
+        };
+        let first = self.crossed.len();
+        for (track_index, track) in self.animation.event_tracks().iter().enumerate() {
+            self.crossed
+                .extend(track.crossed(range).map(|event| CrossedEvent {
+                    track: track_index,
+                    event: event.clone(),
+                }));
+        }
+        let crossed = &mut self.crossed[first..];
+        crossed.sort_by_key(|crossed| crossed.event.frame_offset);
+        if from > to {
+            crossed.reverse();
+        }
+    }
+}
+
+trait AnimationOutput {
+    fn get(&self, target: &Target) -> Value;
+    fn set(&mut self, target: &Target, value: Value);
+}
 
-            if still_running {
-                return false;
-            } else if self.repeat {
-                self.elapsed = Duration::from(remaining)
-                    + Duration::from_nanos(u64::from(self.elapsed.subsec_nanos() % 1_000_000));
+impl AnimationOutput for Skeleton {
+    fn get(&self, target: &Target) -> Value {
+        target.get(self)
+    }
+
+    fn set(&mut self, target: &Target, value: Value) {
+        target.update(value, self);
+    }
+}
+
+struct PoseWriter<'a> {
+    skeleton: &'a Skeleton,
+    pose: &'a mut Pose,
+    additive: bool,
+}
+
+impl AnimationOutput for PoseWriter<'_> {
+    fn get(&self, target: &Target) -> Value {
+        self.pose.get(target).unwrap_or_else(|| {
+            let value = target.get(self.skeleton);
+            if self.additive {
+                value.zeroed()
             } else {
-                return true;
+                value
+            }
+        })
+    }
+
+    fn set(&mut self, target: &Target, value: Value) {
+        if let Some(existing) = self.pose.0.get_mut(target) {
+            *existing = value;
+        } else {
+            self.pose.set(target.clone(), value);
+        }
+    }
+}
+
+#[derive(Default, Debug, PartialEq, Clone)]
+pub struct Pose(BTreeMap<Target, Value>);
+
+impl Pose {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self(BTreeMap::new())
+    }
+
+    #[must_use]
+    pub fn get(&self, target: &Target) -> Option<Value> {
+        self.0.get(target).copied()
+    }
+
+    pub fn set(&mut self, target: Target, value: Value) {
+        self.0.insert(target, value);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Target, Value)> {
+        self.0.iter().map(|(target, value)| (target, *value))
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    #[must_use]
+    pub fn blend(&self, other: &Pose, percent: f32) -> Pose {
+        let mut blended = self.clone();
+        for (target, value) in other.iter() {
+            let value = match self.get(target) {
+                Some(existing) => existing.lerp(value, percent),
+                None => value,
+            };
+            blended.set(target.clone(), value);
+        }
+        blended
+    }
+
+    pub fn apply(&self, skeleton: &mut Skeleton) {
+        for (target, value) in self.iter() {
+            target.update(value, skeleton);
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Mixer {
+    entries: Vec<MixerEntry>,
+}
+
+impl Mixer {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn play(&mut self, animation: RunningAnimation) {
+        self.entries.clear();
+        self.entries.push(MixerEntry::new(animation, None));
+    }
+
+    pub fn crossfade_to(
+        &mut self,
+        animation: RunningAnimation,
+        duration: Duration,
+        easing: impl Into<EasingFunction>,
+    ) {
+        let fade = (!self.entries.is_empty()).then(|| Crossfade {
+            elapsed: Duration::ZERO,
+            duration,
+            easing: easing.into(),
+            base: None,
+        });
+        self.entries.push(MixerEntry::new(animation, fade));
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn update(&mut self, elapsed: Duration, skeleton: &mut Skeleton) -> bool {
+        let mut pose = Pose::new();
+        let finished = self.evaluate(elapsed, skeleton, &mut pose);
+        pose.apply(skeleton);
+        finished
+    }
+
+    pub fn evaluate(&mut self, elapsed: Duration, skeleton: &Skeleton, pose: &mut Pose) -> bool {
+        self.evaluate_with(elapsed, skeleton, pose, false)
+    }
+
+    pub fn evaluate_additive(
+        &mut self,
+        elapsed: Duration,
+        skeleton: &Skeleton,
+        pose: &mut Pose,
+    ) -> bool {
+        self.evaluate_with(elapsed, skeleton, pose, true)
+    }
+
+    fn evaluate_with(
+        &mut self,
+        elapsed: Duration,
+        skeleton: &Skeleton,
+        pose: &mut Pose,
+        additive: bool,
+    ) -> bool {
+        let mut finished = true;
+        for entry in &mut self.entries {
+            if !entry.finished {
+                entry.finished = if additive {
+                    entry
+                        .animation
+                        .evaluate_additive(elapsed, skeleton, &mut entry.pose)
+                } else {
+                    entry.animation.evaluate(elapsed, skeleton, &mut entry.pose)
+                };
+            }
+            finished &= entry.finished;
+            if let Some(fade) = &mut entry.fade {
+                fade.elapsed = (fade.elapsed + elapsed).min(fade.duration);
+                // Targets the outgoing animations don't animate fade in from
+                // their values when the crossfade started.
+                fade.base.get_or_insert_with(|| {
+                    let mut base = Pose::new();
+                    for (target, _) in entry.pose.iter() {
+                        let value = target.get(skeleton);
+                        base.set(
+                            target.clone(),
+                            if additive { value.zeroed() } else { value },
+                        );
+                    }
+                    base
+                });
+            }
+        }
+
+        // Once an animation has fully faded in, the animations beneath it no
+        // longer contribute to the pose.
+        if let Some(opaque) = self
+            .entries
+            .iter()
+            .rposition(|entry| entry.fade.as_ref().map_or(true, Crossfade::is_complete))
+        {
+            self.entries.drain(..opaque);
+        }
+
+        let mut entries = self.entries.iter();
+        let Some(first) = entries.next() else {
+            pose.0.clear();
+            return true;
+        };
+        *pose = first.pose.clone();
+        for entry in entries {
+            let percent = entry.fade.as_ref().map_or(1., Crossfade::percent);
+            if let Some(base) = entry.fade.as_ref().and_then(|fade| fade.base.as_ref()) {
+                for (target, value) in base.iter() {
+                    pose.0.entry(target.clone()).or_insert(value);
+                }
+            }
+            *pose = pose.blend(&entry.pose, percent);
+        }
+
+        finished
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BlendMode {
+    #[default]
+    Override,
+    Additive,
+}
+
+#[derive(Default, Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Mask {
+    bones: BTreeSet<BoneId>,
+    joints: BTreeSet<JointId>,
+}
+
+impl Mask {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            bones: BTreeSet::new(),
+            joints: BTreeSet::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_bone(mut self, bone: BoneId) -> Self {
+        self.bones.insert(bone);
+        self
+    }
+
+    #[must_use]
+    pub fn with_joint(mut self, joint: JointId) -> Self {
+        self.joints.insert(joint);
+        self
+    }
+
+    #[must_use]
+    pub fn with_subtree(mut self, skeleton: &Skeleton, root: BoneId) -> Self {
+        self.insert_subtree(skeleton, root);
+        self
+    }
+
+    pub fn insert_bone(&mut self, bone: BoneId) {
+        self.bones.insert(bone);
+    }
+
+    pub fn insert_joint(&mut self, joint: JointId) {
+        self.joints.insert(joint);
+    }
+
+    pub fn insert_subtree(&mut self, skeleton: &Skeleton, root: BoneId) {
+        let hierarchy = skeleton.hierarchy();
+        for bone in hierarchy.depth_first(root) {
+            self.bones.insert(bone);
+            if let Some(joint) = hierarchy.parent_joint(bone) {
+                self.joints.insert(joint);
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn contains(&self, target: &Target, skeleton: &Skeleton) -> bool {
+        match target {
+            Target::Bone { bone, .. } => self.bones.contains(bone),
+            Target::Joint { joint, .. } => self.joints.contains(joint),
+            Target::LabeledBone { label, .. } => skeleton
+                .find_bone_by_label(label)
+                .is_some_and(|bone| self.bones.contains(&bone)),
+            Target::LabeledJoint { label, .. } => skeleton
+                .find_joint_by_label(label)
+                .is_some_and(|joint| self.joints.contains(&joint)),
+            Target::Skeleton { .. } => false,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Layer {
+    mixer: Mixer,
+    mode: BlendMode,
+    weight: f32,
+    mask: Option<Mask>,
+}
+
+impl Layer {
+    #[must_use]
+    pub const fn new(mode: BlendMode) -> Self {
+        Self {
+            mixer: Mixer::new(),
+            mode,
+            weight: 1.,
+            mask: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_weight(mut self, weight: f32) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    #[must_use]
+    pub fn with_mask(mut self, mask: Mask) -> Self {
+        self.mask = Some(mask);
+        self
+    }
+
+    #[must_use]
+    pub const fn mode(&self) -> BlendMode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: BlendMode) {
+        self.mode = mode;
+    }
+
+    #[must_use]
+    pub const fn weight(&self) -> f32 {
+        self.weight
+    }
+
+    pub fn set_weight(&mut self, weight: f32) {
+        self.weight = weight;
+    }
+
+    #[must_use]
+    pub const fn mask(&self) -> Option<&Mask> {
+        self.mask.as_ref()
+    }
+
+    pub fn set_mask(&mut self, mask: Option<Mask>) {
+        self.mask = mask;
+    }
+
+    #[must_use]
+    pub const fn mixer(&self) -> &Mixer {
+        &self.mixer
+    }
+
+    pub fn mixer_mut(&mut self) -> &mut Mixer {
+        &mut self.mixer
+    }
+}
+
+/// A stack of layers blended from bottom to top.
+///
+/// Layers that add to or partially override a target no lower layer animates
+/// blend onto the target's base value, which is read from the skeleton the
+/// first time the target is animated.
+#[derive(Default)]
+pub struct Layers {
+    layers: Vec<Layer>,
+    base: Pose,
+}
+
+impl Layers {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            layers: Vec::new(),
+            base: Pose::new(),
+        }
+    }
+
+    pub fn push(&mut self, layer: Layer) -> usize {
+        self.layers.push(layer);
+        self.layers.len() - 1
+    }
+
+    #[must_use]
+    pub fn with(mut self, layer: Layer) -> Self {
+        self.push(layer);
+        self
+    }
+
+    pub fn update(&mut self, elapsed: Duration, skeleton: &mut Skeleton) -> bool {
+        let mut pose = Pose::new();
+        let mut layer_pose = Pose::new();
+        let mut finished = true;
+        for layer in &mut self.layers {
+            finished &= match layer.mode {
+                BlendMode::Override => layer.mixer.evaluate(elapsed, skeleton, &mut layer_pose),
+                BlendMode::Additive => {
+                    layer
+                        .mixer
+                        .evaluate_additive(elapsed, skeleton, &mut layer_pose)
+                }
+            };
+
+            for (target, value) in layer_pose.iter() {
+                if layer
+                    .mask
+                    .as_ref()
+                    .is_some_and(|mask| !mask.contains(target, skeleton))
+                {
+                    continue;
+                }
+
+                // The skeleton holds the output of the previous update, so
+                // it can only be read once for the base value.
+                let lower = pose.get(target).unwrap_or_else(|| {
+                    *self
+                        .base
+                        .0
+                        .entry(target.clone())
+                        .or_insert_with(|| target.get(skeleton))
+                });
+                let value = match layer.mode {
+                    BlendMode::Override => value,
+                    BlendMode::Additive => lower + value,
+                };
+                pose.set(target.clone(), lower.lerp(value, layer.weight));
             }
         }
+        pose.apply(skeleton);
+
+        finished
+    }
+}
+
+impl Deref for Layers {
+    type Target = [Layer];
+
+    fn deref(&self) -> &Self::Target {
+        &self.layers
+    }
+}
+
+impl DerefMut for Layers {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.layers
+    }
+}
+
+struct MixerEntry {
+    animation: RunningAnimation,
+    pose: Pose,
+    fade: Option<Crossfade>,
+    finished: bool,
+}
+
+impl MixerEntry {
+    fn new(animation: RunningAnimation, fade: Option<Crossfade>) -> Self {
+        Self {
+            animation,
+            pose: Pose::new(),
+            fade,
+            finished: false,
+        }
+    }
+}
+
+struct Crossfade {
+    elapsed: Duration,
+    duration: Duration,
+    easing: EasingFunction,
+    base: Option<Pose>,
+}
+
+impl Crossfade {
+    fn is_complete(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    fn percent(&self) -> f32 {
+        if self.is_complete() {
+            1.
+        } else {
+            self.easing
+                .ease(self.elapsed.as_secs_f32() / self.duration.as_secs_f32())
+        }
     }
 }
 
@@ -498,54 +1841,6 @@ impl From<Frame> for f32 {
     }
 }
 
-struct RunningTimeline {
-    frame_entry: Frame,
-    frame: usize,
-    frame_start_value: Value,
-    frame_target_value: Value,
-}
-
-impl RunningTimeline {
-    fn update(
-        &mut self,
-        timeline: &Timeline,
-        absolute_frame: Frame,
-        skeleton: &mut Skeleton,
-    ) -> Result<Frame, Frame> {
-        let Some(mut frame) = timeline.frames.get(self.frame) else {
-            return Err(absolute_frame);
-        };
-
-        loop {
-            let relative_frame = absolute_frame - self.frame_entry;
-            let total_frames = frame.frame_offset - self.frame_entry;
-            if relative_frame < frame.frame_offset {
-                let percent = if total_frames > Frame::ZERO {
-                    f32::from(relative_frame) / f32::from(total_frames)
-                } else {
-                    1.
-                };
-                let value = self
-                    .frame_start_value
-                    .lerp(self.frame_target_value, percent);
-                timeline.target.update(value, skeleton);
-                return Ok(frame.frame_offset - relative_frame);
-            }
-
-            self.frame_start_value = self.frame_start_value.lerp(self.frame_target_value, 1.0);
-            timeline.target.update(self.frame_start_value, skeleton);
-            self.frame += 1;
-            self.frame_entry = relative_frame;
-
-            let Some(next_frame) = timeline.frames.get(self.frame) else {
-                return Err(self.frame_entry);
-            };
-            self.frame_target_value = next_frame.update.target(self.frame_start_value);
-            frame = next_frame;
-        }
-    }
-}
-
 trait Lerp: Sized {
     fn lerp(self, target: Self, percent: f32) -> Self;
 }
@@ -573,7 +1868,7 @@ impl Lerp for Rotation {
 
         Self::radians(
             self.radians
-                + if delta_neg.abs() < delta_pos.abs() {
+                + if Scalar::abs(delta_neg) < Scalar::abs(delta_pos) {
                     delta_neg * percent
                 } else {
                     delta_pos * percent
@@ -591,6 +1886,143 @@ impl Lerp for Vector {
     }
 }
 
+trait Spline: Lerp + Copy {
+    fn slope(self, target: Self, seconds: f32) -> Self;
+
+    fn hermite(
+        self,
+        outgoing: Self,
+        target: Self,
+        incoming: Self,
+        seconds: f32,
+        percent: f32,
+    ) -> Self;
+}
+
+impl Spline for f32 {
+    fn slope(self, target: Self, seconds: f32) -> Self {
+        if seconds > 0. {
+            (target - self) / seconds
+        } else {
+            0.
+        }
+    }
+
+    fn hermite(
+        self,
+        outgoing: Self,
+        target: Self,
+        incoming: Self,
+        seconds: f32,
+        percent: f32,
+    ) -> Self {
+        let squared = percent * percent;
+        let cubed = squared * percent;
+        (2. * cubed - 3. * squared + 1.) * self
+            + (cubed - 2. * squared + percent) * seconds * outgoing
+            + (-2. * cubed + 3. * squared) * target
+            + (cubed - squared) * seconds * incoming
+    }
+}
+
+impl Spline for Rotation {
+    fn slope(self, target: Self, seconds: f32) -> Self {
+        Self::radians(self.radians.slope(target.radians, seconds))
+    }
+
+    fn hermite(
+        self,
+        outgoing: Self,
+        target: Self,
+        incoming: Self,
+        seconds: f32,
+        percent: f32,
+    ) -> Self {
+        Self::radians(self.radians.hermite(
+            outgoing.radians,
+            target.radians,
+            incoming.radians,
+            seconds,
+            percent,
+        ))
+    }
+}
+
+impl Spline for Vector {
+    fn slope(self, target: Self, seconds: f32) -> Self {
+        Self::new(
+            self.magnitude.slope(target.magnitude, seconds),
+            self.direction.slope(target.direction, seconds),
+        )
+    }
+
+    fn hermite(
+        self,
+        outgoing: Self,
+        target: Self,
+        incoming: Self,
+        seconds: f32,
+        percent: f32,
+    ) -> Self {
+        Self::new(
+            self.magnitude.hermite(
+                outgoing.magnitude,
+                target.magnitude,
+                incoming.magnitude,
+                seconds,
+                percent,
+            ),
+            self.direction.hermite(
+                outgoing.direction,
+                target.direction,
+                incoming.direction,
+                seconds,
+                percent,
+            ),
+        )
+    }
+}
+
+impl Spline for Value {
+    fn slope(self, target: Self, seconds: f32) -> Self {
+        match (self, target) {
+            (Value::Number(this), Value::Number(target)) => {
+                Value::Number(this.slope(target, seconds))
+            }
+            (Value::Vector(this), Value::Vector(target)) => {
+                Value::Vector(this.slope(target, seconds))
+            }
+            (Value::Bool(_), Value::Bool(_)) => Value::Bool(false),
+            _ => Value::Invalid,
+        }
+    }
+
+    fn hermite(
+        self,
+        outgoing: Self,
+        target: Self,
+        incoming: Self,
+        seconds: f32,
+        percent: f32,
+    ) -> Self {
+        match (self, outgoing, target, incoming) {
+            (
+                Value::Number(this),
+                Value::Number(outgoing),
+                Value::Number(target),
+                Value::Number(incoming),
+            ) => Value::Number(this.hermite(outgoing, target, incoming, seconds, percent)),
+            (
+                Value::Vector(this),
+                Value::Vector(outgoing),
+                Value::Vector(target),
+                Value::Vector(incoming),
+            ) => Value::Vector(this.hermite(outgoing, target, incoming, seconds, percent)),
+            _ => self.lerp(target, percent),
+        }
+    }
+}
+
 impl Lerp for bool {
     fn lerp(self, target: Self, percent: f32) -> Self {
         if percent >= 0.5 {
@@ -640,3 +2072,643 @@ fn basic() {
     assert!(running.update(Duration::from_millis(500), &mut skeleton));
     assert_approx_eq(skeleton[joint].angle().to_radians(), 1.0);
 }
+
+#[test]
+fn labeled_targets() {
+    use alloc::vec;
+
+    use crate::BoneKind;
+
+    let mut skeleton = Skeleton::default();
+    let root = skeleton.push_bone(BoneKind::Rigid { length: 1. }.with_label("root"));
+    let arm = skeleton.push_bone(BoneKind::Rigid { length: 1. }.with_label("arm"));
+    let shoulder = skeleton.push_joint(
+        Joint::new(Rotation::default(), root.axis_b(), arm.axis_a()).with_label("shoulder"),
+    );
+
+    let animation = Animation::default()
+        .with(
+            Timeline::new(Target::LabeledJoint {
+                label: String::from("shoulder"),
+                property: JointProperty::Angle,
+            })
+            .with_frame(Keyframe::new(
+                Frame::ZERO,
+                PropertyUpdate::ChangeTo(Value::from(1.)),
+            )),
+        )
+        .with(Timeline::new(Target::LabeledBone {
+            label: String::from("leg"),
+            property: BoneProperty::Target,
+        }))
+        .with(Timeline::new(Target::LabeledJoint {
+            label: String::from("hip"),
+            property: JointProperty::Angle,
+        }));
+
+    let Err(errors) = animation.try_start(&skeleton) else {
+        unreachable!("labels should not resolve")
+    };
+    assert_eq!(
+        errors,
+        vec![
+            UnresolvedLabel {
+                timeline: 1,
+                label: Label::Bone(String::from("leg")),
+            },
+            UnresolvedLabel {
+                timeline: 2,
+                label: Label::Joint(String::from("hip")),
+            },
+        ]
+    );
+
+    let mut animation = animation.with(Timeline::new(Target::Joint {
+        joint: shoulder,
+        property: JointProperty::Angle,
+    }));
+    animation.remove(2);
+    animation.remove(1);
+    let resolved = animation.resolve(&skeleton).expect("all labels resolve");
+    assert_eq!(
+        resolved[0].target(),
+        &Target::Joint {
+            joint: shoulder,
+            property: JointProperty::Angle,
+        }
+    );
+    let mut running = resolved.start();
+    running.update(Duration::ZERO, &mut skeleton);
+    assert!((skeleton[shoulder].angle().to_radians() - 1.).abs() < 0.0001);
+}
+
+#[test]
+fn retargeting() {
+    use crate::BoneKind;
+
+    fn rig(arm_length: f32) -> (Skeleton, BoneId, JointId) {
+        let mut skeleton = Skeleton::default();
+        // Inserting an extra bone ensures ids differ between the rigs.
+        if arm_length > 1. {
+            skeleton.push_bone(BoneKind::Rigid { length: 1. }.with_label("root"));
+        }
+        let arm = skeleton.push_bone(
+            BoneKind::Jointed {
+                start_length: arm_length / 2.,
+                end_length: arm_length / 2.,
+                inverse: false,
+            }
+            .with_label("arm"),
+        );
+        let hand = skeleton.push_bone(BoneKind::Rigid { length: 0.5 }.with_label("hand"));
+        let wrist = skeleton.push_joint(
+            Joint::new(Rotation::default(), arm.axis_b(), hand.axis_a()).with_label("wrist"),
+        );
+        (skeleton, arm, wrist)
+    }
+
+    let (short, short_arm, short_wrist) = rig(1.);
+    let (tall, tall_arm, tall_wrist) = rig(2.);
+
+    let reach = Vector::new(1., Rotation::degrees(45.));
+    let animation = Animation::default()
+        .with(
+            Timeline::new(Target::Bone {
+                bone: short_arm,
+                property: BoneProperty::Target,
+            })
+            .with_frame(Keyframe::new(
+                Frame::ZERO,
+                PropertyUpdate::ChangeTo(Value::from(reach)),
+            )),
+        )
+        .with(
+            Timeline::new(Target::Joint {
+                joint: short_wrist,
+                property: JointProperty::Angle,
+            })
+            .with_frame(Keyframe::new(
+                Frame::ZERO,
+                PropertyUpdate::ChangeTo(Value::from(1.)),
+            )),
+        );
+
+    let retargeted = animation
+        .retargeted(&short, &tall)
+        .expect("all targets match");
+    assert_eq!(
+        retargeted[0].target(),
+        &Target::Bone {
+            bone: tall_arm,
+            property: BoneProperty::Target,
+        }
+    );
+    assert_eq!(
+        retargeted[0][0].update,
+        PropertyUpdate::ChangeTo(Value::from(Vector::new(2., reach.direction)))
+    );
+    assert_eq!(
+        retargeted[1].target(),
+        &Target::Joint {
+            joint: tall_wrist,
+            property: JointProperty::Angle,
+        }
+    );
+    assert_eq!(retargeted[1][0].update, animation[1][0].update);
+
+    // Unlabeled and missing targets are reported rather than dropped.
+    let mut other = Skeleton::default();
+    let arm = other.push_bone(BoneKind::Rigid { length: 1. });
+    let hand = other.push_bone(BoneKind::Rigid { length: 1. }.with_label("hand"));
+    other.push_joint(Joint::new(Rotation::default(), arm.axis_b(), hand.axis_a()));
+    let missing = Target::LabeledBone {
+        label: String::from("tail"),
+        property: BoneProperty::Target,
+    };
+    let Err(unmatched) = animation
+        .clone()
+        .with(Timeline::new(missing.clone()))
+        .retargeted(&short, &other)
+    else {
+        unreachable!("targets should not match")
+    };
+    assert_eq!(
+        unmatched,
+        [
+            UnmatchedTarget {
+                timeline: 0,
+                target: animation[0].target().clone(),
+            },
+            UnmatchedTarget {
+                timeline: 1,
+                target: animation[1].target().clone(),
+            },
+            UnmatchedTarget {
+                timeline: 2,
+                target: missing,
+            },
+        ]
+    );
+}
+
+#[test]
+fn skipped_keyframes() {
+    use crate::BoneKind;
+
+    let mut skeleton = Skeleton::default();
+    let root = skeleton.push_bone(BoneKind::Rigid { length: 1. });
+    let arm = skeleton.push_bone(BoneKind::Rigid { length: 1. });
+    let joint = skeleton.push_joint(Joint::new(Rotation::default(), root.axis_b(), arm.axis_a()));
+
+    let mut timeline = Timeline::new(Target::Joint {
+        joint,
+        property: JointProperty::Angle,
+    });
+    for (millis, angle) in [(100, 1.), (200, 3.), (400, 5.)] {
+        timeline.insert_frame(Keyframe::new(
+            Frame::from(millis),
+            PropertyUpdate::ChangeTo(Value::from(angle)),
+        ));
+    }
+    let animation = Animation::default().with(timeline);
+    let angle = |skeleton: &Skeleton| skeleton[joint].angle().to_radians();
+
+    // A single update that passes several keyframes must interpolate within
+    // the segment it lands in, measured from that segment's start.
+    let mut running = animation.start();
+    running.update(Duration::from_millis(300), &mut skeleton);
+    assert!((angle(&skeleton) - 4.).abs() < 0.0001);
+    running.update(Duration::from_millis(50), &mut skeleton);
+    assert!((angle(&skeleton) - 4.5).abs() < 0.0001);
+}
+
+#[test]
+fn crossfade() {
+    use easing_function::easings::StandardEasing;
+
+    use crate::BoneKind;
+
+    let mut skeleton = Skeleton::default();
+    let root = skeleton.push_bone(BoneKind::Rigid { length: 1. });
+    let arm = skeleton.push_bone(BoneKind::Rigid { length: 1. });
+    let joint = skeleton.push_joint(Joint::new(Rotation::default(), root.axis_b(), arm.axis_a()));
+
+    let hold = |angle: f32| {
+        Animation::default().with(
+            Timeline::new(Target::Joint {
+                joint,
+                property: JointProperty::Angle,
+            })
+            .with_frame(Keyframe::new(
+                Frame::ZERO,
+                PropertyUpdate::ChangeTo(Value::from(angle)),
+            )),
+        )
+    };
+
+    let mut mixer = Mixer::new();
+    mixer.play(hold(0.).start());
+    mixer.update(Duration::ZERO, &mut skeleton);
+    assert!(skeleton[joint].angle().to_radians().abs() < 0.0001);
+
+    mixer.crossfade_to(
+        hold(1.).start(),
+        Duration::from_secs(1),
+        StandardEasing::Linear,
+    );
+    mixer.update(Duration::from_millis(500), &mut skeleton);
+    assert!((skeleton[joint].angle().to_radians() - 0.5).abs() < 0.0001);
+    mixer.update(Duration::from_millis(500), &mut skeleton);
+    assert!((skeleton[joint].angle().to_radians() - 1.).abs() < 0.0001);
+    assert_eq!(mixer.entries.len(), 1);
+
+    // A target only the incoming animation animates fades in from its value
+    // when the crossfade started.
+    let hand = skeleton.push_bone(BoneKind::Rigid { length: 1. });
+    let wrist = skeleton.push_joint(Joint::new(Rotation::default(), arm.axis_b(), hand.axis_a()));
+    let wave = Animation::default().with(
+        Timeline::new(Target::Joint {
+            joint: wrist,
+            property: JointProperty::Angle,
+        })
+        .with_frame(Keyframe::new(
+            Frame::ZERO,
+            PropertyUpdate::ChangeTo(Value::from(1.)),
+        )),
+    );
+    mixer.crossfade_to(wave.start(), Duration::from_secs(1), StandardEasing::Linear);
+    mixer.update(Duration::from_millis(100), &mut skeleton);
+    assert!((skeleton[wrist].angle().to_radians() - 0.1).abs() < 0.0001);
+    assert!((skeleton[joint].angle().to_radians() - 1.).abs() < 0.0001);
+    mixer.update(Duration::from_millis(400), &mut skeleton);
+    assert!((skeleton[wrist].angle().to_radians() - 0.5).abs() < 0.0001);
+    mixer.update(Duration::from_millis(500), &mut skeleton);
+    assert!((skeleton[wrist].angle().to_radians() - 1.).abs() < 0.0001);
+    assert!((skeleton[joint].angle().to_radians() - 1.).abs() < 0.0001);
+}
+
+#[test]
+fn layers() {
+    use crate::BoneKind;
+
+    let mut skeleton = Skeleton::default();
+    let root = skeleton.push_bone(BoneKind::Rigid { length: 1. });
+    let arm = skeleton.push_bone(BoneKind::Rigid { length: 1. });
+    let shoulder =
+        skeleton.push_joint(Joint::new(Rotation::default(), root.axis_b(), arm.axis_a()));
+    let hand = skeleton.push_bone(BoneKind::Rigid { length: 1. });
+    let wrist = skeleton.push_joint(Joint::new(Rotation::default(), arm.axis_b(), hand.axis_a()));
+
+    let animate = |update: PropertyUpdate| {
+        Animation::default()
+            .with(
+                Timeline::new(Target::Joint {
+                    joint: shoulder,
+                    property: JointProperty::Angle,
+                })
+                .with_frame(Keyframe::new(Frame::ZERO, update)),
+            )
+            .with(
+                Timeline::new(Target::Joint {
+                    joint: wrist,
+                    property: JointProperty::Angle,
+                })
+                .with_frame(Keyframe::new(Frame::ZERO, update)),
+            )
+            .start()
+    };
+    let angle = |skeleton: &Skeleton, joint: JointId| skeleton[joint].angle().to_radians();
+
+    let mut base = Layer::new(BlendMode::Override);
+    base.mixer_mut()
+        .play(animate(PropertyUpdate::ChangeTo(Value::from(1.))));
+    let mut additive = Layer::new(BlendMode::Additive)
+        .with_weight(0.5)
+        .with_mask(Mask::new().with_subtree(&skeleton, hand));
+    additive
+        .mixer_mut()
+        .play(animate(PropertyUpdate::Add(Value::from(0.5))));
+
+    let mut layers = Layers::new().with(base).with(additive);
+    layers.update(Duration::ZERO, &mut skeleton);
+    assert!((angle(&skeleton, shoulder) - 1.).abs() < 0.0001);
+    assert!((angle(&skeleton, wrist) - 1.25).abs() < 0.0001);
+
+    layers[1].set_weight(1.);
+    layers[1].set_mask(None);
+    layers.update(Duration::ZERO, &mut skeleton);
+    assert!((angle(&skeleton, shoulder) - 1.5).abs() < 0.0001);
+    assert!((angle(&skeleton, wrist) - 1.5).abs() < 0.0001);
+
+    // Layers blending onto targets no lower layer animates don't compound
+    // across updates.
+    skeleton[shoulder].set_angle(Rotation::default());
+    skeleton[wrist].set_angle(Rotation::default());
+    let mut additive = Layer::new(BlendMode::Additive).with_mask(Mask::new().with_joint(shoulder));
+    additive
+        .mixer_mut()
+        .play(animate(PropertyUpdate::Add(Value::from(0.5))));
+    let mut partial = Layer::new(BlendMode::Override)
+        .with_weight(0.5)
+        .with_mask(Mask::new().with_joint(wrist));
+    partial
+        .mixer_mut()
+        .play(animate(PropertyUpdate::ChangeTo(Value::from(1.))));
+    let mut layers = Layers::new().with(additive).with(partial);
+    for _ in 0..4 {
+        layers.update(Duration::from_millis(16), &mut skeleton);
+        assert!((angle(&skeleton, shoulder) - 0.5).abs() < 0.0001);
+        assert!((angle(&skeleton, wrist) - 0.5).abs() < 0.0001);
+    }
+}
+
+#[test]
+fn events() {
+    use alloc::vec;
+
+    use crate::BoneKind;
+
+    let mut skeleton = Skeleton::default();
+    let root = skeleton.push_bone(BoneKind::Rigid { length: 1. });
+    let arm = skeleton.push_bone(BoneKind::Rigid { length: 1. });
+    let joint = skeleton.push_joint(Joint::new(Rotation::default(), root.axis_b(), arm.axis_a()));
+
+    let animation = Animation::default()
+        .with(
+            Timeline::new(Target::Joint {
+                joint,
+                property: JointProperty::Angle,
+            })
+            .with_frame(Keyframe::new(
+                Frame::from(1_000),
+                PropertyUpdate::ChangeTo(Value::from(1.)),
+            )),
+        )
+        .with_event_track(
+            EventTrack::new("feet")
+                .with_event(Event::new(Frame::ZERO, "left").with_payload(0.5))
+                .with_event(Event::new(Frame::from(500), "right")),
+        )
+        .with_event_track(
+            EventTrack::new("sound").with_event(Event::new(Frame::from(250), "step")),
+        );
+
+    let names = |running: &RunningAnimation| {
+        running
+            .crossed_events()
+            .iter()
+            .map(|crossed| (crossed.track, crossed.event.name.clone()))
+            .collect::<Vec<_>>()
+    };
+
+    let mut running = animation.start().looping();
+    running.update(Duration::ZERO, &mut skeleton);
+    assert_eq!(names(&running), vec![(0, String::from("left"))]);
+    assert_eq!(
+        running.crossed_events()[0].event.payload,
+        Some(Value::from(0.5))
+    );
+    running.update(Duration::from_millis(600), &mut skeleton);
+    assert_eq!(
+        names(&running),
+        vec![(1, String::from("step")), (0, String::from("right"))]
+    );
+    // Wrap around the end of the loop.
+    running.update(Duration::from_millis(700), &mut skeleton);
+    assert_eq!(
+        names(&running),
+        vec![(0, String::from("left")), (1, String::from("step"))]
+    );
+    assert!((skeleton[joint].angle().to_radians() - 0.3).abs() < 0.0001);
+}
+
+#[test]
+fn playback() {
+    use alloc::vec;
+
+    use crate::BoneKind;
+
+    let mut skeleton = Skeleton::default();
+    let root = skeleton.push_bone(BoneKind::Rigid { length: 1. });
+    let arm = skeleton.push_bone(BoneKind::Rigid { length: 1. });
+    let joint = skeleton.push_joint(Joint::new(Rotation::default(), root.axis_b(), arm.axis_a()));
+
+    let animation = Animation::default()
+        .with(
+            Timeline::new(Target::Joint {
+                joint,
+                property: JointProperty::Angle,
+            })
+            .with_frame(Keyframe::new(
+                Frame::ZERO,
+                PropertyUpdate::ChangeTo(Value::from(0.)),
+            ))
+            .with_frame(Keyframe::new(
+                Frame::from(1_000),
+                PropertyUpdate::Add(Value::from(1.)),
+            )),
+        )
+        .with_event_track(
+            EventTrack::new("marks").with_event(Event::new(Frame::from(800), "mark")),
+        );
+    let angle = |skeleton: &Skeleton| skeleton[joint].angle().to_radians();
+    let crossed = |running: &RunningAnimation| running.crossed_events().len();
+
+    // Seeking produces the same pose as playing forward.
+    let mut running = animation.start();
+    running.seek(Duration::from_millis(700));
+    running.update(Duration::ZERO, &mut skeleton);
+    assert!((angle(&skeleton) - 0.7).abs() < 0.0001);
+    running.seek(Duration::from_millis(200));
+    running.update(Duration::ZERO, &mut skeleton);
+    assert!((angle(&skeleton) - 0.2).abs() < 0.0001);
+
+    // Arriving exactly at the end finishes the animation.
+    let mut running = animation.start();
+    assert!(!running.update(Duration::from_millis(400), &mut skeleton));
+    assert!(running.update(Duration::from_millis(600), &mut skeleton));
+    assert!((angle(&skeleton) - 1.).abs() < 0.0001);
+    assert_eq!(crossed(&running), 1);
+
+    // Rates that aren't finite are ignored, and huge rates don't overflow.
+    let mut running = animation.start().with_rate(f32::NAN);
+    running.set_rate(f32::INFINITY);
+    assert!((running.rate() - 1.).abs() < f32::EPSILON);
+    assert!(!running.update(Duration::from_millis(500), &mut skeleton));
+    assert!((angle(&skeleton) - 0.5).abs() < 0.0001);
+    running.set_rate(f32::MAX);
+    assert!(running.update(Duration::from_secs(1), &mut skeleton));
+    assert!((angle(&skeleton) - 1.).abs() < 0.0001);
+
+    // Reverse playback starts from the end.
+    let mut running = animation.start().with_rate(-2.);
+    assert!(!running.update(Duration::from_millis(150), &mut skeleton));
+    assert!((angle(&skeleton) - 0.7).abs() < 0.0001);
+    assert_eq!(crossed(&running), 1);
+    assert!(running.update(Duration::from_millis(500), &mut skeleton));
+    assert!(angle(&skeleton).abs() < 0.0001);
+
+    // A ping-pong cycle is a trip forward and back.
+    let mut running = animation.start().ping_pong().with_repeat_count(1);
+    assert!(!running.update(Duration::from_millis(1_300), &mut skeleton));
+    assert!((angle(&skeleton) - 0.7).abs() < 0.0001);
+    assert_eq!(
+        running
+            .crossed_events()
+            .iter()
+            .map(|crossed| crossed.event.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["mark", "mark"]
+    );
+    assert!(!running.update(Duration::from_millis(200), &mut skeleton));
+    assert!((angle(&skeleton) - 0.5).abs() < 0.0001);
+    assert_eq!(crossed(&running), 0);
+    assert!(running.update(Duration::from_millis(600), &mut skeleton));
+    assert!(angle(&skeleton).abs() < 0.0001);
+
+    // Seeking restarts the direction and the repeat count.
+    let mut running = animation.start().ping_pong().with_repeat_count(1);
+    running.update(Duration::from_millis(1_300), &mut skeleton);
+    running.seek(Duration::from_millis(200));
+    assert!(!running.update(Duration::from_millis(100), &mut skeleton));
+    assert!((angle(&skeleton) - 0.3).abs() < 0.0001);
+    assert!(!running.update(Duration::from_millis(900), &mut skeleton));
+    assert!((angle(&skeleton) - 0.8).abs() < 0.0001);
+    assert!(running.update(Duration::from_millis(800), &mut skeleton));
+    assert!(angle(&skeleton).abs() < 0.0001);
+
+    // Repeating counts each pass through the animation.
+    let mut running = animation.start().looping().with_repeat_count(2);
+    assert!(!running.update(Duration::from_millis(1_500), &mut skeleton));
+    assert!((angle(&skeleton) - 0.5).abs() < 0.0001);
+    assert!(running.update(Duration::from_millis(600), &mut skeleton));
+    assert!((angle(&skeleton) - 1.).abs() < 0.0001);
+}
+
+#[test]
+fn sampling() {
+    use crate::BoneKind;
+
+    let mut skeleton = Skeleton::default();
+    let root = skeleton.push_bone(BoneKind::Rigid { length: 1. });
+    let arm = skeleton.push_bone(BoneKind::Rigid { length: 1. });
+    let joint = skeleton.push_joint(Joint::new(Rotation::default(), root.axis_b(), arm.axis_a()));
+    let target = Target::Joint {
+        joint,
+        property: JointProperty::Angle,
+    };
+
+    let animation = Animation::default().with(
+        Timeline::new(target.clone())
+            .with_frame(Keyframe::new(
+                Frame::from(500),
+                PropertyUpdate::Add(Value::from(1.)),
+            ))
+            .with_frame(Keyframe::new(
+                Frame::from(1_000),
+                PropertyUpdate::ChangeTo(Value::from(0.)),
+            )),
+    );
+    let mut rest = animation.rest_pose(&skeleton);
+    assert_eq!(rest.get(&target), Some(Value::from(0.)));
+    rest.set(target.clone(), Value::from(1.));
+
+    // Sampling is independent of the order of the samples and never modifies
+    // the skeleton.
+    let late = animation.sample(Duration::from_millis(750), &rest);
+    let early = animation.sample(Duration::from_millis(250), &rest);
+    assert_eq!(early.get(&target), Some(Value::from(1.5)));
+    assert_eq!(late.get(&target), Some(Value::from(1.)));
+    assert_eq!(
+        animation.sample(Duration::from_secs(2), &rest).get(&target),
+        Some(Value::from(0.))
+    );
+    assert_eq!(skeleton[joint].angle(), Rotation::default());
+
+    // Sampling matches playing the animation to the same time.
+    skeleton[joint].set_angle(Rotation::radians(1.));
+    let mut running = animation.start();
+    running.update(Duration::from_millis(750), &mut skeleton);
+    assert!((skeleton[joint].angle().to_radians() - 1.).abs() < 0.0001);
+}
+
+#[test]
+fn splines() {
+    let target = Target::LabeledJoint {
+        label: String::from("shoulder"),
+        property: JointProperty::Angle,
+    };
+    let timeline = |tangent: Tangent| {
+        Animation::default().with(
+            Timeline::new(target.clone())
+                .with_frame(Keyframe::new(
+                    Frame::ZERO,
+                    PropertyUpdate::ChangeTo(Value::from(0.)),
+                ))
+                .with_frame(
+                    Keyframe::new(Frame::from(1_000), PropertyUpdate::Add(Value::from(1.)))
+                        .with_tangent(tangent),
+                )
+                .with_frame(Keyframe::new(
+                    Frame::from(2_000),
+                    PropertyUpdate::ChangeTo(Value::from(0.)),
+                )),
+        )
+    };
+    let sample = |animation: &Animation, millis: u64| {
+        let Some(Value::Number(value)) = animation
+            .sample(Duration::from_millis(millis), &Pose::new())
+            .get(&target)
+        else {
+            unreachable!("number animated")
+        };
+        value
+    };
+
+    let linear = timeline(Tangent::Linear);
+    assert!((sample(&linear, 500) - 0.5).abs() < 0.0001);
+    assert!((sample(&linear, 1_500) - 0.5).abs() < 0.0001);
+
+    // The neighbors are level, so the automatic tangent is flat.
+    let auto = timeline(Tangent::Auto);
+    assert!((sample(&auto, 500) - 0.625).abs() < 0.0001);
+    assert!((sample(&auto, 1_000) - 1.).abs() < 0.0001);
+    assert!((sample(&auto, 1_500) - 0.625).abs() < 0.0001);
+    let flat = timeline(Tangent::Flat);
+    assert!((sample(&flat, 500) - sample(&auto, 500)).abs() < 0.0001);
+
+    let broken = timeline(Tangent::Broken {
+        incoming: Value::from(2.),
+        outgoing: Value::from(0.),
+    });
+    assert!((sample(&broken, 500) - 0.375).abs() < 0.0001);
+    assert!((sample(&broken, 1_500) - 0.625).abs() < 0.0001);
+
+    // Vectors are interpolated per component.
+    let target = Target::LabeledBone {
+        label: String::from("arm"),
+        property: BoneProperty::Target,
+    };
+    let vectors = Animation::default().with(
+        Timeline::new(target.clone())
+            .with_frame(Keyframe::new(
+                Frame::ZERO,
+                PropertyUpdate::ChangeTo(Value::from(Vector::new(1., Rotation::radians(0.)))),
+            ))
+            .with_frame(
+                Keyframe::new(
+                    Frame::from(1_000),
+                    PropertyUpdate::ChangeTo(Value::from(Vector::new(2., Rotation::radians(1.)))),
+                )
+                .with_tangent(Tangent::Flat),
+            ),
+    );
+    let Some(Value::Vector(vector)) = vectors
+        .sample(Duration::from_millis(500), &Pose::new())
+        .get(&target)
+    else {
+        unreachable!("vector animated")
+    };
+    assert!((vector.magnitude - 1.625).abs() < 0.0001);
+    assert!((vector.direction.to_radians() - 0.625).abs() < 0.0001);
+}
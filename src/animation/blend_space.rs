@@ -0,0 +1,284 @@
+use alloc::vec::Vec;
+use core::time::Duration;
+
+use super::{Animation, Pose};
+use crate::{Coordinate, Skeleton};
+
+#[derive(Default, Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlendSpace1D {
+    samples: Vec<(f32, Animation)>,
+}
+
+impl BlendSpace1D {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            samples: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, position: f32, animation: Animation) {
+        let index = self
+            .samples
+            .partition_point(|(existing, _)| *existing <= position);
+        self.samples.insert(index, (position, animation));
+    }
+
+    #[must_use]
+    pub fn with(mut self, position: f32, animation: Animation) -> Self {
+        self.push(position, animation);
+        self
+    }
+
+    #[must_use]
+    pub fn samples(&self) -> &[(f32, Animation)] {
+        &self.samples
+    }
+
+    #[must_use]
+    pub fn weights(&self, parameter: f32) -> Vec<f32> {
+        let mut weights = alloc::vec![0.; self.samples.len()];
+        let after = self
+            .samples
+            .partition_point(|(position, _)| *position <= parameter);
+        match (after.checked_sub(1), self.samples.get(after)) {
+            (Some(before), Some((next, _))) => {
+                let previous = self.samples[before].0;
+                let percent = (parameter - previous) / (next - previous);
+                weights[before] = 1. - percent;
+                weights[after] = percent;
+            }
+            (Some(before), None) => weights[before] = 1.,
+            (None, Some(_)) => weights[after] = 1.,
+            (None, None) => {}
+        }
+        weights
+    }
+
+    #[must_use]
+    pub fn start(&self) -> RunningBlendSpace {
+        RunningBlendSpace::new(Space::OneDimensional(self.clone()))
+    }
+}
+
+#[derive(Default, Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlendSpace2D {
+    samples: Vec<(Coordinate, Animation)>,
+}
+
+impl BlendSpace2D {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            samples: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, position: Coordinate, animation: Animation) {
+        self.samples.push((position, animation));
+    }
+
+    #[must_use]
+    pub fn with(mut self, position: Coordinate, animation: Animation) -> Self {
+        self.push(position, animation);
+        self
+    }
+
+    #[must_use]
+    pub fn samples(&self) -> &[(Coordinate, Animation)] {
+        &self.samples
+    }
+
+    /// Computes weights using gradient band interpolation.
+    #[must_use]
+    pub fn weights(&self, parameter: Coordinate) -> Vec<f32> {
+        fn dot(a: Coordinate, b: Coordinate) -> f32 {
+            a.x * b.x + a.y * b.y
+        }
+
+        let mut weights = self
+            .samples
+            .iter()
+            .map(|(position, _)| {
+                let to_parameter = parameter - *position;
+                self.samples
+                    .iter()
+                    .map(|(other, _)| *other - *position)
+                    .filter(|&to_other| dot(to_other, to_other) > 0.)
+                    .fold(1_f32, |weight, to_other| {
+                        let influence = 1. - dot(to_parameter, to_other) / dot(to_other, to_other);
+                        weight.min(influence.clamp(0., 1.))
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        let total = weights.iter().sum::<f32>();
+        if total > 0. {
+            for weight in &mut weights {
+                *weight /= total;
+            }
+        }
+        weights
+    }
+
+    #[must_use]
+    pub fn start(&self) -> RunningBlendSpace {
+        RunningBlendSpace::new(Space::TwoDimensional(self.clone()))
+    }
+}
+
+enum Space {
+    OneDimensional(BlendSpace1D),
+    TwoDimensional(BlendSpace2D),
+}
+
+impl Space {
+    fn animations(&self) -> Vec<&Animation> {
+        match self {
+            Space::OneDimensional(space) => space.samples.iter().map(|(_, a)| a).collect(),
+            Space::TwoDimensional(space) => space.samples.iter().map(|(_, a)| a).collect(),
+        }
+    }
+
+    fn weights(&self, parameter: Coordinate) -> Vec<f32> {
+        match self {
+            Space::OneDimensional(space) => space.weights(parameter.x),
+            Space::TwoDimensional(space) => space.weights(parameter),
+        }
+    }
+}
+
+pub struct RunningBlendSpace {
+    space: Space,
+    parameter: Coordinate,
+    phase: f32,
+    rest: Option<Pose>,
+}
+
+impl RunningBlendSpace {
+    fn new(space: Space) -> Self {
+        Self {
+            space,
+            parameter: Coordinate::default(),
+            phase: 0.,
+            rest: None,
+        }
+    }
+
+    /// Sets the current parameter. One-dimensional blend spaces ignore `y`.
+    pub fn set_parameter(&mut self, x: f32, y: f32) {
+        self.parameter = Coordinate::new(x, y);
+    }
+
+    #[must_use]
+    pub const fn parameter(&self) -> Coordinate {
+        self.parameter
+    }
+
+    #[must_use]
+    pub const fn phase(&self) -> f32 {
+        self.phase
+    }
+
+    pub fn update(&mut self, elapsed: Duration, skeleton: &mut Skeleton) {
+        let mut pose = Pose::new();
+        self.evaluate(elapsed, skeleton, &mut pose);
+        pose.apply(skeleton);
+    }
+
+    pub fn evaluate(&mut self, elapsed: Duration, skeleton: &Skeleton, pose: &mut Pose) {
+        let animations = self.space.animations();
+        let rest = self.rest.get_or_insert_with(|| {
+            let mut rest = Pose::new();
+            for animation in &animations {
+                for (target, value) in animation.rest_pose(skeleton).iter() {
+                    rest.set(target.clone(), value);
+                }
+            }
+            rest
+        });
+        let weights = self.space.weights(self.parameter);
+
+        // Each animation is stretched to the weighted average of the
+        // durations, keeping cycles of different lengths in sync.
+        let duration = animations
+            .iter()
+            .zip(&weights)
+            .map(|(animation, weight)| animation.duration().as_secs_f32() * weight)
+            .sum::<f32>();
+        if duration > 0. {
+            self.phase = (self.phase + elapsed.as_secs_f32() / duration).fract();
+        }
+
+        pose.clone_from(rest);
+        let mut total_weight = 0.;
+        for (animation, weight) in animations.into_iter().zip(weights) {
+            if weight <= 0. {
+                continue;
+            }
+            let mut sample = rest.clone();
+            for (target, value) in animation
+                .sample(animation.duration().mul_f32(self.phase), rest)
+                .iter()
+            {
+                sample.set(target.clone(), value);
+            }
+            total_weight += weight;
+            *pose = pose.blend(&sample, weight / total_weight);
+        }
+    }
+}
+
+#[test]
+fn blend_spaces() {
+    use super::{Frame, JointProperty, Keyframe, PropertyUpdate, Target, Timeline, Value};
+    use crate::{BoneKind, Joint, Rotation};
+
+    let mut skeleton = Skeleton::default();
+    let root = skeleton.push_bone(BoneKind::Rigid { length: 1. });
+    let arm = skeleton.push_bone(BoneKind::Rigid { length: 1. });
+    let joint = skeleton.push_joint(Joint::new(Rotation::default(), root.axis_b(), arm.axis_a()));
+
+    let swing = |millis: u32, angle: f32| {
+        Animation::default().with(
+            Timeline::new(Target::Joint {
+                joint,
+                property: JointProperty::Angle,
+            })
+            .with_frame(Keyframe::new(
+                Frame::ZERO,
+                PropertyUpdate::ChangeTo(Value::from(0.)),
+            ))
+            .with_frame(Keyframe::new(
+                Frame::from(millis),
+                PropertyUpdate::ChangeTo(Value::from(angle)),
+            )),
+        )
+    };
+
+    let space = BlendSpace1D::new()
+        .with(1., swing(500, 2.))
+        .with(0., swing(1_000, 1.));
+    assert_eq!(space.weights(-1.), [1., 0.]);
+    assert_eq!(space.weights(0.25), [0.75, 0.25]);
+    assert_eq!(space.weights(2.), [0., 1.]);
+
+    let mut running = space.start();
+    running.set_parameter(0.5, 0.);
+    // The blended cycle lasts 750ms, so this is halfway through each cycle.
+    running.update(Duration::from_millis(375), &mut skeleton);
+    assert!((running.phase() - 0.5).abs() < 0.0001);
+    assert!((skeleton[joint].angle().to_radians() - 0.75).abs() < 0.0001);
+
+    let space = BlendSpace2D::new()
+        .with(Coordinate::new(0., 0.), Animation::default())
+        .with(Coordinate::new(1., 0.), Animation::default())
+        .with(Coordinate::new(0., 1.), Animation::default());
+    assert_eq!(space.weights(Coordinate::new(0., 0.)), [1., 0., 0.]);
+    assert_eq!(space.weights(Coordinate::new(0., 1.)), [0., 0., 1.]);
+    let weights = space.weights(Coordinate::new(0.25, 0.25));
+    assert!((weights.iter().sum::<f32>() - 1.).abs() < 0.0001);
+    assert!(weights[0] > weights[1] && (weights[1] - weights[2]).abs() < 0.0001);
+}
@@ -0,0 +1,329 @@
+use easing_function::{easings::StandardEasing, Easing, EasingFunction};
+
+use crate::Scalar;
+
+/// The easing applied to the segment of a timeline leading into a keyframe.
+///
+/// Standard easings are serialized as a bare [`StandardEasing`], which is the
+/// format keyframes used before other easings were supported.
+#[derive(Debug, PartialEq, Clone)]
+pub enum KeyframeEasing {
+    Standard(StandardEasing),
+    CubicBezier(CubicBezier),
+    Steps(Steps),
+    /// An arbitrary easing function. Custom easing functions can't be
+    /// serialized: serializing a keyframe using one returns an error.
+    Custom(EasingFunction),
+}
+
+impl Default for KeyframeEasing {
+    fn default() -> Self {
+        Self::Standard(StandardEasing::default())
+    }
+}
+
+impl Easing for KeyframeEasing {
+    fn ease(&self, progress: f32) -> f32 {
+        match self {
+            KeyframeEasing::Standard(easing) => easing.ease(progress),
+            KeyframeEasing::CubicBezier(easing) => easing.ease(progress),
+            KeyframeEasing::Steps(easing) => easing.ease(progress),
+            KeyframeEasing::Custom(easing) => easing.ease(progress),
+        }
+    }
+}
+
+impl From<StandardEasing> for KeyframeEasing {
+    fn from(easing: StandardEasing) -> Self {
+        Self::Standard(easing)
+    }
+}
+
+impl From<CubicBezier> for KeyframeEasing {
+    fn from(easing: CubicBezier) -> Self {
+        Self::CubicBezier(easing)
+    }
+}
+
+impl From<Steps> for KeyframeEasing {
+    fn from(easing: Steps) -> Self {
+        Self::Steps(easing)
+    }
+}
+
+impl From<EasingFunction> for KeyframeEasing {
+    fn from(easing: EasingFunction) -> Self {
+        Self::Custom(easing)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for KeyframeEasing {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            KeyframeEasing::Standard(easing) => easing.serialize(serializer),
+            KeyframeEasing::CubicBezier(easing) => {
+                TaggedEasing::CubicBezier(*easing).serialize(serializer)
+            }
+            KeyframeEasing::Steps(easing) => TaggedEasing::Steps(*easing).serialize(serializer),
+            KeyframeEasing::Custom(_) => Err(serde::ser::Error::custom(
+                "custom easing functions can't be serialized",
+            )),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for KeyframeEasing {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(serde::Deserialize)]
+        #[serde(untagged)]
+        enum Format {
+            Standard(StandardEasing),
+            Tagged(TaggedEasing),
+        }
+
+        Ok(match Format::deserialize(deserializer)? {
+            Format::Standard(easing) | Format::Tagged(TaggedEasing::Standard(easing)) => {
+                KeyframeEasing::Standard(easing)
+            }
+            Format::Tagged(TaggedEasing::CubicBezier(easing)) => {
+                KeyframeEasing::CubicBezier(easing)
+            }
+            Format::Tagged(TaggedEasing::Steps(easing)) => KeyframeEasing::Steps(easing),
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+enum TaggedEasing {
+    Standard(StandardEasing),
+    CubicBezier(CubicBezier),
+    Steps(Steps),
+}
+
+/// A cubic Bézier curve from `(0, 0)` to `(1, 1)` with two control points,
+/// matching CSS's `cubic-bezier()`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CubicBezier {
+    pub x1: f32,
+    pub y1: f32,
+    pub x2: f32,
+    pub y2: f32,
+}
+
+impl CubicBezier {
+    #[must_use]
+    pub const fn new(x1: f32, y1: f32, x2: f32, y2: f32) -> Self {
+        Self { x1, y1, x2, y2 }
+    }
+
+    fn sample(a: f32, b: f32, t: f32) -> f32 {
+        let inverse = 1. - t;
+        3. * inverse * inverse * t * a + 3. * inverse * t * t * b + t * t * t
+    }
+
+    fn slope(a: f32, b: f32, t: f32) -> f32 {
+        let inverse = 1. - t;
+        3. * inverse * inverse * a + 6. * inverse * t * (b - a) + 3. * t * t * (1. - b)
+    }
+
+    fn solve_t(&self, x: f32) -> f32 {
+        let mut t = x;
+        for _ in 0..8 {
+            let error = Self::sample(self.x1, self.x2, t) - x;
+            let slope = Self::slope(self.x1, self.x2, t);
+            if Scalar::abs(error) < 1e-6 || Scalar::abs(slope) < 1e-6 {
+                break;
+            }
+            t -= error / slope;
+        }
+
+        if (0. ..=1.).contains(&t) && Scalar::abs(Self::sample(self.x1, self.x2, t) - x) < 1e-5 {
+            return t;
+        }
+
+        // Newton's method didn't converge, fall back to bisection.
+        let (mut low, mut high) = (0_f32, 1_f32);
+        t = x;
+        for _ in 0..32 {
+            if Self::sample(self.x1, self.x2, t) < x {
+                low = t;
+            } else {
+                high = t;
+            }
+            t = (low + high) / 2.;
+        }
+        t
+    }
+}
+
+impl Easing for CubicBezier {
+    fn ease(&self, progress: f32) -> f32 {
+        if progress <= 0. {
+            0.
+        } else if progress >= 1. {
+            1.
+        } else {
+            Self::sample(self.y1, self.y2, self.solve_t(progress))
+        }
+    }
+}
+
+/// Divides progress into a number of equal jumps, matching CSS's `steps()`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Steps {
+    pub count: u32,
+    pub position: StepPosition,
+}
+
+impl Steps {
+    #[must_use]
+    pub const fn new(count: u32, position: StepPosition) -> Self {
+        Self { count, position }
+    }
+}
+
+impl Easing for Steps {
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    fn ease(&self, progress: f32) -> f32 {
+        if self.count == 0 || progress >= 1. {
+            return 1.;
+        }
+
+        let count = self.count as f32;
+        let step = ((progress.max(0.) * count) as u32).min(self.count - 1);
+        match self.position {
+            StepPosition::Start => (step + 1) as f32 / count,
+            StepPosition::End => step as f32 / count,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StepPosition {
+    /// The first jump happens at the start of the segment.
+    Start,
+    /// The last jump happens at the end of the segment.
+    #[default]
+    End,
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn keyframe_easing() {
+    use core::time::Duration;
+
+    use alloc::string::String;
+
+    use super::{
+        Animation, Frame, JointProperty, Keyframe, Pose, PropertyUpdate, Target, Timeline, Value,
+    };
+
+    let steps = Steps::new(4, StepPosition::End);
+    assert_eq!(steps.ease(0.), 0.);
+    assert_eq!(steps.ease(0.3), 0.25);
+    assert_eq!(steps.ease(1.), 1.);
+    assert_eq!(Steps::new(4, StepPosition::Start).ease(0.3), 0.5);
+
+    let linear = CubicBezier::new(0.25, 0.25, 0.75, 0.75);
+    assert!((linear.ease(0.3) - 0.3).abs() < 0.0001);
+    let ease_in = CubicBezier::new(0.42, 0., 1., 1.);
+    assert!(ease_in.ease(0.5) < 0.5);
+    assert!((ease_in.ease(0.9999) - 1.).abs() < 0.001);
+
+    let target = Target::LabeledJoint {
+        label: String::from("shoulder"),
+        property: JointProperty::Angle,
+    };
+    let animation = Animation::default().with(
+        Timeline::new(target.clone())
+            .with_frame(Keyframe::new(
+                Frame::ZERO,
+                PropertyUpdate::ChangeTo(Value::from(0.)),
+            ))
+            .with_frame(
+                Keyframe::new(
+                    Frame::from(1_000),
+                    PropertyUpdate::ChangeTo(Value::from(1.)),
+                )
+                .with_easing(StandardEasing::EaseInQuadradic),
+            )
+            .with_frame(
+                Keyframe::new(
+                    Frame::from(2_000),
+                    PropertyUpdate::ChangeTo(Value::from(2.)),
+                )
+                .with_easing(Steps::new(2, StepPosition::End)),
+            ),
+    );
+    let sample = |millis: u64| {
+        animation
+            .sample(Duration::from_millis(millis), &Pose::new())
+            .get(&target)
+    };
+    assert_eq!(sample(500), Some(Value::from(0.25)));
+    assert_eq!(sample(1_400), Some(Value::from(1.)));
+    assert_eq!(sample(1_600), Some(Value::from(1.5)));
+
+    let custom = KeyframeEasing::from(EasingFunction::from(StandardEasing::EaseInQuadradic));
+    assert!((custom.ease(0.5) - 0.25).abs() < 0.0001);
+
+    #[cfg(feature = "serde")]
+    {
+        #[derive(serde::Serialize)]
+        struct LegacyKeyframe {
+            frame_offset: Frame,
+            update: PropertyUpdate,
+            easing: StandardEasing,
+        }
+
+        for easing in [
+            KeyframeEasing::from(StandardEasing::EaseOutQuadradic),
+            KeyframeEasing::from(ease_in),
+            KeyframeEasing::from(steps),
+        ] {
+            let serialized = pot::to_vec(&easing).unwrap();
+            assert_eq!(
+                pot::from_slice::<KeyframeEasing>(&serialized).unwrap(),
+                easing
+            );
+        }
+        assert!(pot::to_vec(&custom).is_err());
+        let animation = Animation::default().with(
+            Timeline::new(target).with_frame(
+                Keyframe::new(Frame::ZERO, PropertyUpdate::ChangeTo(Value::from(0.)))
+                    .with_easing(custom),
+            ),
+        );
+        assert!(pot::to_vec(&animation).is_err());
+
+        // Keyframes saved before other easings were supported still load.
+        let legacy = pot::to_vec(&LegacyKeyframe {
+            frame_offset: Frame::from(100),
+            update: PropertyUpdate::ChangeTo(Value::from(1.)),
+            easing: StandardEasing::EaseInQuadradic,
+        })
+        .unwrap();
+        let keyframe: Keyframe = pot::from_slice(&legacy).unwrap();
+        assert_eq!(
+            keyframe,
+            Keyframe::new(Frame::from(100), PropertyUpdate::ChangeTo(Value::from(1.)))
+                .with_easing(StandardEasing::EaseInQuadradic)
+        );
+    }
+}
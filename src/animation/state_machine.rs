@@ -0,0 +1,451 @@
+use alloc::{
+    borrow::{Cow, ToOwned},
+    collections::BTreeMap,
+    string::String,
+    vec::Vec,
+};
+use core::time::Duration;
+
+use easing_function::easings::StandardEasing;
+
+use super::{Animation, Mixer, RunningAnimation};
+use crate::Skeleton;
+
+#[derive(Default, Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StateMachine {
+    states: Vec<State>,
+    transitions: Vec<Transition>,
+    initial: StateId,
+}
+
+impl StateMachine {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            states: Vec::new(),
+            transitions: Vec::new(),
+            initial: StateId(0),
+        }
+    }
+
+    pub fn push_state(&mut self, state: State) -> StateId {
+        let id = StateId(self.states.len());
+        self.states.push(state);
+        id
+    }
+
+    pub fn push_transition(&mut self, transition: Transition) {
+        self.transitions.push(transition);
+    }
+
+    #[must_use]
+    pub fn with_transition(mut self, transition: Transition) -> Self {
+        self.push_transition(transition);
+        self
+    }
+
+    #[must_use]
+    pub fn states(&self) -> &[State] {
+        &self.states
+    }
+
+    #[must_use]
+    pub fn state(&self, id: StateId) -> Option<&State> {
+        self.states.get(id.0)
+    }
+
+    #[must_use]
+    pub fn transitions(&self) -> &[Transition] {
+        &self.transitions
+    }
+
+    #[must_use]
+    pub fn find_state_by_name(&self, name: &str) -> Option<StateId> {
+        self.states
+            .iter()
+            .position(|state| state.name == name)
+            .map(StateId)
+    }
+
+    #[must_use]
+    pub const fn initial(&self) -> StateId {
+        self.initial
+    }
+
+    pub fn set_initial(&mut self, initial: StateId) {
+        self.initial = initial;
+    }
+
+    #[must_use]
+    pub fn start(&self) -> RunningStateMachine {
+        let mut mixer = Mixer::new();
+        if let Some(state) = self.state(self.initial) {
+            mixer.play(state.start());
+        }
+        RunningStateMachine {
+            machine: self.clone(),
+            current: self.initial,
+            elapsed: Duration::ZERO,
+            variables: BTreeMap::new(),
+            mixer,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Ord, PartialOrd, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StateId(usize);
+
+impl StateId {
+    #[must_use]
+    pub const fn index(self) -> usize {
+        self.0
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct State {
+    pub name: String,
+    pub animation: Animation,
+    pub looping: bool,
+}
+
+impl State {
+    #[must_use]
+    pub fn new(name: impl Into<String>, animation: Animation) -> Self {
+        Self {
+            name: name.into(),
+            animation,
+            looping: false,
+        }
+    }
+
+    #[must_use]
+    pub fn looping(mut self) -> Self {
+        self.looping = true;
+        self
+    }
+
+    fn start(&self) -> RunningAnimation {
+        let running = self.animation.start();
+        if self.looping {
+            running.looping()
+        } else {
+            running
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Transition {
+    pub from: Option<StateId>,
+    pub to: StateId,
+    pub conditions: Vec<Condition>,
+    pub exit_time: Option<Duration>,
+    pub crossfade: Duration,
+    pub easing: StandardEasing,
+}
+
+impl Transition {
+    #[must_use]
+    pub fn new(from: StateId, to: StateId) -> Self {
+        Self {
+            from: Some(from),
+            to,
+            conditions: Vec::new(),
+            exit_time: None,
+            crossfade: Duration::ZERO,
+            easing: StandardEasing::default(),
+        }
+    }
+
+    #[must_use]
+    pub fn from_any(to: StateId) -> Self {
+        Self {
+            from: None,
+            ..Self::new(to, to)
+        }
+    }
+
+    #[must_use]
+    pub fn when(mut self, condition: Condition) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    #[must_use]
+    pub fn with_exit_time(mut self, exit_time: Duration) -> Self {
+        self.exit_time = Some(exit_time);
+        self
+    }
+
+    #[must_use]
+    pub fn with_crossfade(mut self, duration: Duration, easing: StandardEasing) -> Self {
+        self.crossfade = duration;
+        self.easing = easing;
+        self
+    }
+
+    fn applies(
+        &self,
+        current: StateId,
+        elapsed: Duration,
+        variable: impl Fn(&str) -> Option<f32>,
+    ) -> bool {
+        match self.from {
+            Some(from) if from != current => return false,
+            None if self.to == current => return false,
+            _ => {}
+        }
+
+        self.exit_time
+            .map_or(true, |exit_time| elapsed >= exit_time)
+            && self
+                .conditions
+                .iter()
+                .all(|condition| condition.is_met(&variable))
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Condition {
+    pub variable: String,
+    pub comparison: Comparison,
+    pub value: f32,
+}
+
+impl Condition {
+    #[must_use]
+    pub fn new(variable: impl Into<String>, comparison: Comparison, value: f32) -> Self {
+        Self {
+            variable: variable.into(),
+            comparison,
+            value,
+        }
+    }
+
+    #[must_use]
+    pub fn is_met(&self, variable: impl Fn(&str) -> Option<f32>) -> bool {
+        variable(&self.variable).is_some_and(|value| self.comparison.compare(value, self.value))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+    NotEqual,
+}
+
+impl Comparison {
+    #[must_use]
+    #[allow(clippy::float_cmp)]
+    pub fn compare(self, lhs: f32, rhs: f32) -> bool {
+        match self {
+            Comparison::Less => lhs < rhs,
+            Comparison::LessOrEqual => lhs <= rhs,
+            Comparison::Greater => lhs > rhs,
+            Comparison::GreaterOrEqual => lhs >= rhs,
+            Comparison::Equal => lhs == rhs,
+            Comparison::NotEqual => lhs != rhs,
+        }
+    }
+}
+
+pub struct RunningStateMachine {
+    machine: StateMachine,
+    current: StateId,
+    elapsed: Duration,
+    variables: BTreeMap<String, f32>,
+    mixer: Mixer,
+}
+
+impl RunningStateMachine {
+    pub fn set_variable<'a>(&mut self, name: impl Into<Cow<'a, str>>, value: f32) {
+        match name.into() {
+            Cow::Owned(name) => {
+                self.variables.insert(name, value);
+            }
+            Cow::Borrowed(name) => {
+                if let Some(var) = self.variables.get_mut(name) {
+                    *var = value;
+                } else {
+                    self.variables.insert(name.to_owned(), value);
+                }
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn variable(&self, name: &str) -> Option<f32> {
+        self.variables.get(name).copied().or_else(|| {
+            self.machine
+                .state(self.current)
+                .and_then(|state| state.animation.variable(name))
+        })
+    }
+
+    #[must_use]
+    pub const fn current(&self) -> StateId {
+        self.current
+    }
+
+    #[must_use]
+    pub const fn machine(&self) -> &StateMachine {
+        &self.machine
+    }
+
+    pub fn update(&mut self, elapsed: Duration, skeleton: &mut Skeleton) -> bool {
+        self.elapsed += elapsed;
+        let transition = self
+            .machine
+            .transitions
+            .iter()
+            .find(|transition| {
+                transition.applies(self.current, self.elapsed, |name| self.variable(name))
+            })
+            .cloned();
+        if let Some(transition) = transition {
+            if let Some(state) = self.machine.state(transition.to) {
+                // Only the part of this update after the transition fired
+                // plays in the new state. Conditions were met before the
+                // update started, while an exit time can be reached partway
+                // through it.
+                let remaining = transition.exit_time.map_or(elapsed, |exit_time| {
+                    self.elapsed.saturating_sub(exit_time).min(elapsed)
+                });
+                if remaining < elapsed {
+                    self.mixer
+                        .update(elapsed.saturating_sub(remaining), skeleton);
+                }
+                self.current = transition.to;
+                self.elapsed = remaining;
+                self.mixer
+                    .crossfade_to(state.start(), transition.crossfade, transition.easing);
+                return self.mixer.update(remaining, skeleton);
+            }
+        }
+
+        self.mixer.update(elapsed, skeleton)
+    }
+}
+
+#[test]
+fn state_machine() {
+    use super::{Frame, JointProperty, Keyframe, PropertyUpdate, Target, Timeline, Value};
+    use crate::{BoneKind, Joint, Rotation};
+
+    let mut skeleton = Skeleton::default();
+    let root = skeleton.push_bone(BoneKind::Rigid { length: 1. });
+    let arm = skeleton.push_bone(BoneKind::Rigid { length: 1. });
+    let joint = skeleton.push_joint(Joint::new(Rotation::default(), root.axis_b(), arm.axis_a()));
+
+    let hold = |angle: f32| {
+        Animation::default().with(
+            Timeline::new(Target::Joint {
+                joint,
+                property: JointProperty::Angle,
+            })
+            .with_frame(Keyframe::new(
+                Frame::ZERO,
+                PropertyUpdate::ChangeTo(Value::from(angle)),
+            )),
+        )
+    };
+
+    let mut machine = StateMachine::new();
+    let idle = machine.push_state(State::new("idle", hold(0.)));
+    let walk = machine.push_state(State::new("walk", hold(1.)));
+    let wave = machine.push_state(State::new("wave", hold(2.)));
+    let machine = machine
+        .with_transition(
+            Transition::new(idle, walk)
+                .when(Condition::new("speed", Comparison::Greater, 0.5))
+                .with_crossfade(Duration::from_secs(1), StandardEasing::Linear),
+        )
+        .with_transition(Transition::new(walk, idle).when(Condition::new(
+            "speed",
+            Comparison::LessOrEqual,
+            0.5,
+        )))
+        .with_transition(Transition::from_any(wave).when(Condition::new(
+            "waving",
+            Comparison::Equal,
+            1.,
+        )))
+        .with_transition(Transition::new(wave, idle).with_exit_time(Duration::from_secs(2)));
+
+    #[cfg(feature = "serde")]
+    {
+        let serialized = pot::to_vec(&machine).unwrap();
+        let deserialized: StateMachine = pot::from_slice(&serialized).unwrap();
+        assert_eq!(deserialized.states().len(), 3);
+        assert_eq!(deserialized.transitions(), machine.transitions());
+    }
+
+    let angle = |skeleton: &Skeleton| skeleton[joint].angle().to_radians();
+    let mut running = machine.start();
+    running.update(Duration::ZERO, &mut skeleton);
+    assert_eq!(running.current(), idle);
+    assert!(angle(&skeleton).abs() < 0.0001);
+
+    running.set_variable("speed", 1.);
+    running.update(Duration::from_millis(500), &mut skeleton);
+    assert_eq!(running.current(), walk);
+    assert!((angle(&skeleton) - 0.5).abs() < 0.0001);
+    running.update(Duration::from_millis(500), &mut skeleton);
+    assert!((angle(&skeleton) - 1.).abs() < 0.0001);
+
+    running.set_variable("waving", 1.);
+    running.update(Duration::ZERO, &mut skeleton);
+    assert_eq!(running.current(), wave);
+    assert!((angle(&skeleton) - 2.).abs() < 0.0001);
+
+    running.set_variable("waving", 0.);
+    running.set_variable("speed", 0.);
+    running.update(Duration::from_secs(1), &mut skeleton);
+    assert_eq!(running.current(), wave);
+    running.update(Duration::from_secs(1), &mut skeleton);
+    assert_eq!(running.current(), idle);
+
+    // A transition reached partway through an update only plays the new state
+    // for the time remaining after its exit time.
+    let mut machine = StateMachine::new();
+    let idle = machine.push_state(State::new("idle", hold(0.)));
+    let raise = machine.push_state(State::new(
+        "raise",
+        Animation::default().with(
+            Timeline::new(Target::Joint {
+                joint,
+                property: JointProperty::Angle,
+            })
+            .with_frame(Keyframe::new(
+                Frame::ZERO,
+                PropertyUpdate::ChangeTo(Value::from(0.)),
+            ))
+            .with_frame(Keyframe::new(
+                Frame::from(1_000),
+                PropertyUpdate::ChangeTo(Value::from(1.)),
+            )),
+        ),
+    ));
+    let machine = machine.with_transition(
+        Transition::new(idle, raise)
+            .with_exit_time(Duration::from_secs(1))
+            .with_crossfade(Duration::from_secs(1), StandardEasing::Linear),
+    );
+    let mut running = machine.start();
+    running.update(Duration::from_millis(1_250), &mut skeleton);
+    assert_eq!(running.current(), raise);
+    assert!((angle(&skeleton) - 0.0625).abs() < 0.0001);
+    running.update(Duration::from_millis(250), &mut skeleton);
+    assert!((angle(&skeleton) - 0.25).abs() < 0.0001);
+}
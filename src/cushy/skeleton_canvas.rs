@@ -1,7 +1,7 @@
 #![allow(missing_docs)]
 use core::f32;
 
-use crate::{Rotation, BoneEnd, BoneId, Coordinate, JointId, Skeleton, Vector};
+use crate::{Rotation, BoneEnd, BoneId, Coordinate, JointId, Skeleton, Vector, YAxis};
 use cushy::{
     context::{EventContext, GraphicsContext, LayoutContext, Trackable},
     figures::{
@@ -32,6 +32,7 @@ pub struct SkeletonCanvas {
     maximum_scale: Dynamic<f32>,
     minimum_scale: Dynamic<f32>,
     offset: Point<Px>,
+    y_axis: YAxis,
     drag: Option<DragInfo>,
     on_mutate: Option<Callback<SkeletonMutation>>,
 }
@@ -49,6 +50,7 @@ impl SkeletonCanvas {
             maximum_scale,
             minimum_scale,
             offset: Point::default(),
+            y_axis: YAxis::default(),
             drag: None,
             on_mutate: None,
         }
@@ -78,18 +80,27 @@ impl SkeletonCanvas {
         self
     }
 
+    fn flip(&self, coordinate: Coordinate) -> Coordinate {
+        match self.y_axis {
+            YAxis::Down => coordinate,
+            YAxis::Up => Coordinate::new(coordinate.x, -coordinate.y),
+        }
+    }
+
     fn coordinate_to_point(&self, vector: Coordinate) -> Point<Px> {
-        (vector * self.scale.get())
+        (self.flip(vector) * self.scale.get())
             .to_vec::<Point<f32>>()
             .map(Px::from)
             + self.offset
     }
 
     fn point_to_coordinate(&self, position: Point<Px>) -> Coordinate {
-        (position - self.offset)
-            .map(FloatConversion::into_float)
-            .to_vec::<Coordinate>()
-            / self.scale.get()
+        self.flip(
+            (position - self.offset)
+                .map(FloatConversion::into_float)
+                .to_vec::<Coordinate>()
+                / self.scale.get(),
+        )
     }
 }
 
@@ -103,6 +114,7 @@ impl Widget for SkeletonCanvas {
             return;
         }
         skeleton.solve();
+        self.y_axis = skeleton.coordinate_system().y_axis;
         let root_start = skeleton.bones()[0].start();
         let (min, max) = skeleton.bones().iter().fold(
             (Coordinate::new(f32::MAX, f32::MAX), Coordinate::default()),
@@ -146,7 +158,7 @@ impl Widget for SkeletonCanvas {
         let handle_size = Lp::mm(2).into_px(context.gfx.scale()).ceil();
         self.handle_size = handle_size.into_float() / scale;
 
-        let root = root_start * scale;
+        let root = self.flip(root_start) * scale;
 
         self.offset = (middle - root).to_vec::<Point<f32>>().map(Px::from).floor();
 
@@ -195,7 +207,7 @@ impl Widget for SkeletonCanvas {
                 let end = if let Some(desired_end) =
                     bone.solved_joint().and_then(|_| bone.desired_end())
                 {
-                    bone.start() + (desired_end + bone.entry_angle())
+                    bone.start() + skeleton.to_world_offset(desired_end + bone.entry_angle())
                 } else {
                     bone.end()
                 };
@@ -234,7 +246,10 @@ impl Widget for SkeletonCanvas {
                 );
                 if let Some(desired_end) = bone.desired_end() {
                     distance = distance.min(
-                        (location - bone.start() - (desired_end + bone.entry_angle())).magnitude(),
+                        (location
+                            - bone.start()
+                            - skeleton.to_world_offset(desired_end + bone.entry_angle()))
+                        .magnitude(),
                     );
                 }
 
@@ -323,7 +338,7 @@ impl Widget for SkeletonCanvas {
                             BoneEnd::A => start,
                             BoneEnd::B => bone_a.end(),
                         };
-                        let new_bone_rotation = (location - end).as_rotation();
+                        let new_bone_rotation = skeleton.to_local_vector(location - end).direction;
                         let rotation = new_bone_rotation - bone_a_rotation;
                         drop(skeleton);
 
@@ -340,7 +355,7 @@ impl Widget for SkeletonCanvas {
                             if skeleton.generation == 0 {
                                 skeleton.solve();
                             }
-                            let end = Vector::from(location - skeleton[bone].start())
+                            let end = skeleton.to_local_vector(location - skeleton[bone].start())
                                 - skeleton[bone].entry_angle();
                             drop(skeleton);
                             on_mutate.invoke(SkeletonMutation::SetDesiredEnd { bone, end });
@@ -0,0 +1,452 @@
+use core::{
+    cmp::Ordering,
+    fmt::{Debug, Display},
+    ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign},
+};
+
+use crate::Scalar;
+
+/// A deterministic fixed-point number with 32 integer bits and 32 fractional
+/// bits.
+///
+/// All arithmetic and trigonometry performed by this type is implemented using
+/// integer operations. Skeletons solved using `Fixed` (e.g.,
+/// [`generic::Skeleton<Fixed>`](crate::generic::Skeleton)) produce
+/// bit-identical results on every platform, which makes this type suitable
+/// for lockstep simulations.
+///
+/// Arithmetic saturates at [`Fixed::MIN`] and [`Fixed::MAX`] rather than
+/// overflowing. Dividing by zero saturates in the direction of the dividend's
+/// sign, or results in zero if the dividend is also zero.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub struct Fixed(i64);
+
+impl Fixed {
+    /// The number of bits used to represent the fractional part of the value.
+    pub const FRACTIONAL_BITS: u32 = 32;
+    /// The largest value representable by this type.
+    pub const MAX: Self = Self(i64::MAX);
+    /// The smallest value representable by this type.
+    pub const MIN: Self = Self(i64::MIN);
+
+    const HALF_PI: i128 = Self::PI.0 as i128 / 2;
+    const TWO_PI: i128 = Self::PI.0 as i128 * 2;
+    const LN_2: i128 = 2_977_044_472;
+    /// The reciprocal of the gain introduced by [`cordic_rotate`].
+    const CORDIC_GAIN_INVERSE: i128 = 2_608_131_496;
+
+    /// Returns a value from its underlying representation.
+    #[must_use]
+    pub const fn from_bits(bits: i64) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the underlying representation of this value.
+    #[must_use]
+    pub const fn to_bits(self) -> i64 {
+        self.0
+    }
+
+    /// Returns the closest `f32` to this value.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn to_f32(self) -> f32 {
+        self.to_f64() as f32
+    }
+
+    /// Returns the closest `f64` to this value.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / (1_u64 << Self::FRACTIONAL_BITS) as f64
+    }
+
+    fn saturating(value: i128) -> Self {
+        Self(i64::try_from(value).unwrap_or(if value < 0 { i64::MIN } else { i64::MAX }))
+    }
+
+    /// Returns the sine and cosine of this angle.
+    fn sin_cos(self) -> (Self, Self) {
+        let mut angle = i128::from(self.0) % Self::TWO_PI;
+        if angle > i128::from(Self::PI.0) {
+            angle -= Self::TWO_PI;
+        } else if angle < -i128::from(Self::PI.0) {
+            angle += Self::TWO_PI;
+        }
+
+        // CORDIC only converges for angles between -π/2 and π/2. Rotating by
+        // π negates both the sine and cosine.
+        let negate = if angle > Self::HALF_PI {
+            angle -= i128::from(Self::PI.0);
+            true
+        } else if angle < -Self::HALF_PI {
+            angle += i128::from(Self::PI.0);
+            true
+        } else {
+            false
+        };
+
+        let (cos, sin) = cordic_rotate(Self::CORDIC_GAIN_INVERSE, 0, angle);
+        if negate {
+            (Self::saturating(-sin), Self::saturating(-cos))
+        } else {
+            (Self::saturating(sin), Self::saturating(cos))
+        }
+    }
+}
+
+/// The arctangent of `2^-i` for each CORDIC iteration `i`.
+const ATAN_TABLE: [i128; 32] = [
+    3_373_259_426,
+    1_991_351_318,
+    1_052_175_346,
+    534_100_635,
+    268_086_748,
+    134_174_063,
+    67_103_403,
+    33_553_749,
+    16_777_131,
+    8_388_597,
+    4_194_303,
+    2_097_152,
+    1_048_576,
+    524_288,
+    262_144,
+    131_072,
+    65_536,
+    32_768,
+    16_384,
+    8_192,
+    4_096,
+    2_048,
+    1_024,
+    512,
+    256,
+    128,
+    64,
+    32,
+    16,
+    8,
+    4,
+    2,
+];
+
+/// Rotates `x`,`y` by `angle`, returning the rotated vector scaled by the
+/// CORDIC gain.
+fn cordic_rotate(mut x: i128, mut y: i128, mut angle: i128) -> (i128, i128) {
+    for (i, atan) in ATAN_TABLE.iter().enumerate() {
+        let dx = y >> i;
+        let dy = x >> i;
+        if angle >= 0 {
+            x -= dx;
+            y += dy;
+            angle -= atan;
+        } else {
+            x += dx;
+            y -= dy;
+            angle += atan;
+        }
+    }
+    (x, y)
+}
+
+/// Returns the angle of the vector `x`,`y`, in radians.
+fn cordic_angle(mut x: i128, mut y: i128) -> i128 {
+    if x == 0 && y == 0 {
+        return 0;
+    }
+
+    // CORDIC only converges for vectors with a positive x component.
+    let mut angle = 0;
+    if x < 0 {
+        angle = if y >= 0 {
+            i128::from(Fixed::PI.0)
+        } else {
+            -i128::from(Fixed::PI.0)
+        };
+        x = -x;
+        y = -y;
+    }
+
+    for (i, atan) in ATAN_TABLE.iter().enumerate() {
+        let dx = y >> i;
+        let dy = x >> i;
+        if y > 0 {
+            x += dx;
+            y -= dy;
+            angle += atan;
+        } else {
+            x -= dx;
+            y += dy;
+            angle -= atan;
+        }
+    }
+    angle
+}
+
+/// Returns the integer square root of `n`.
+fn isqrt(mut n: u128) -> u128 {
+    let mut result = 0;
+    let mut bit = 1 << 126;
+    while bit > n {
+        bit >>= 2;
+    }
+    while bit != 0 {
+        if n >= result + bit {
+            n -= result + bit;
+            result = (result >> 1) + bit;
+        } else {
+            result >>= 1;
+        }
+        bit >>= 2;
+    }
+    result
+}
+
+impl Scalar for Fixed {
+    const ZERO: Self = Self(0);
+    const ONE: Self = Self(1 << Self::FRACTIONAL_BITS);
+    const PI: Self = Self(13_493_037_705);
+    const EPSILON: Self = Self(1);
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn from_f32(value: f32) -> Self {
+        // Multiplying by a power of two is exact, and the conversion to an
+        // integer truncates, making this conversion deterministic.
+        Self((f64::from(value) * f64::from(1_u32 << 16) * f64::from(1_u32 << 16)) as i64)
+    }
+
+    fn abs(self) -> Self {
+        Self(self.0.saturating_abs())
+    }
+
+    fn sqrt(self) -> Self {
+        if self.0 <= 0 {
+            return Self::ZERO;
+        }
+        let root = isqrt(u128::from(self.0.unsigned_abs()) << Self::FRACTIONAL_BITS);
+        Self::saturating(i128::try_from(root).unwrap_or(i128::MAX))
+    }
+
+    fn sin(self) -> Self {
+        self.sin_cos().0
+    }
+
+    fn cos(self) -> Self {
+        self.sin_cos().1
+    }
+
+    fn acos(self) -> Self {
+        let x = Scalar::clamp(self, -Self::ONE, Self::ONE);
+        (Self::ONE - x * x).sqrt().atan2(x)
+    }
+
+    fn atan2(self, x: Self) -> Self {
+        Self::saturating(cordic_angle(i128::from(x.0), i128::from(self.0)))
+    }
+
+    fn exp(self) -> Self {
+        // e^x = 2^k * e^r, where k is an integer and r is between -ln(2)/2
+        // and ln(2)/2.
+        let x = i128::from(self.0);
+        let k = (x + Self::LN_2 / 2).div_euclid(Self::LN_2);
+        let r = Self::saturating(x - k * Self::LN_2);
+
+        let mut term = Self::ONE;
+        let mut sum = Self::ONE;
+        for n in 1..=12 {
+            term = Self::saturating(
+                ((i128::from(term.0) * i128::from(r.0)) >> Self::FRACTIONAL_BITS) / n,
+            );
+            sum += term;
+        }
+
+        if k >= 0 {
+            if k >= 63 {
+                Self::MAX
+            } else {
+                Self::saturating(i128::from(sum.0) << k)
+            }
+        } else if k <= -64 {
+            Self::ZERO
+        } else {
+            Self(sum.0 >> -k)
+        }
+    }
+}
+
+impl Add for Fixed {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0.saturating_add(rhs.0))
+    }
+}
+
+impl AddAssign for Fixed {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0.saturating_sub(rhs.0))
+    }
+}
+
+impl SubAssign for Fixed {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul for Fixed {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::saturating((i128::from(self.0) * i128::from(rhs.0)) >> Self::FRACTIONAL_BITS)
+    }
+}
+
+impl Div for Fixed {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        if rhs.0 == 0 {
+            match self.0.cmp(&0) {
+                Ordering::Less => Self::MIN,
+                Ordering::Equal => Self::ZERO,
+                Ordering::Greater => Self::MAX,
+            }
+        } else {
+            Self::saturating((i128::from(self.0) << Self::FRACTIONAL_BITS) / i128::from(rhs.0))
+        }
+    }
+}
+
+impl Neg for Fixed {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self(self.0.saturating_neg())
+    }
+}
+
+impl From<i32> for Fixed {
+    fn from(value: i32) -> Self {
+        Self(i64::from(value) << Self::FRACTIONAL_BITS)
+    }
+}
+
+impl Debug for Fixed {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("Fixed").field(&self.to_f64()).finish()
+    }
+}
+
+impl Display for Fixed {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&self.to_f64(), f)
+    }
+}
+
+#[test]
+fn accuracy() {
+    #[track_caller]
+    fn assert_close(fixed: Fixed, expected: f64) {
+        assert!(
+            (fixed.to_f64() - expected).abs() < 1e-7,
+            "{fixed} != {expected}"
+        );
+    }
+
+    for degrees in (-720..=720).step_by(15) {
+        let radians = f64::from(degrees).to_radians();
+        #[allow(clippy::cast_possible_truncation)]
+        let angle = Fixed::from_f32(radians as f32);
+        let radians = angle.to_f64();
+        assert_close(angle.sin(), radians.sin());
+        assert_close(angle.cos(), radians.cos());
+        assert_close(
+            angle.sin().atan2(angle.cos()),
+            radians.sin().atan2(radians.cos()),
+        );
+    }
+
+    for value in [0., 0.25, 1., 2., 10.5, 12345.] {
+        assert_close(Fixed::from_f32(value).sqrt(), f64::from(value).sqrt());
+    }
+    for value in [-5., -1., -0.25, 0., 0.5, 1., 3.] {
+        assert_close(Fixed::from_f32(value).exp(), f64::from(value).exp());
+    }
+    for value in [-1., -0.5, 0., 0.3, 1.] {
+        assert_close(Fixed::from_f32(value).acos(), f64::from(value).acos());
+    }
+}
+
+#[test]
+fn golden_pose() {
+    use crate::{
+        generic::{BoneKind, Joint, Rotation, Skeleton, Vector},
+        BoneId,
+    };
+
+    let mut skeleton = Skeleton::<Fixed>::default();
+    skeleton.set_rotation(Rotation::degrees(Fixed::from(-90)));
+    let spine = skeleton.push_bone(BoneKind::Rigid {
+        length: Fixed::from(3),
+    });
+    let hip = skeleton.push_bone(BoneKind::Rigid {
+        length: Fixed::from_f32(0.5),
+    });
+    skeleton.push_joint(Joint::new(
+        Rotation::degrees(Fixed::from(90)),
+        spine.axis_a(),
+        hip.axis_a(),
+    ));
+    let leg = skeleton.push_bone(BoneKind::Jointed {
+        start_length: Fixed::from_f32(1.5),
+        end_length: Fixed::from_f32(1.5),
+        inverse: true,
+    });
+    skeleton.push_joint(Joint::new(
+        Rotation::degrees(Fixed::from(-90)),
+        hip.axis_b(),
+        leg.axis_a(),
+    ));
+    skeleton[leg].set_desired_end(Some(Vector::new(
+        Fixed::from_f32(2.25),
+        Rotation::degrees(Fixed::from(20)),
+    )));
+    let foot = skeleton.push_bone(BoneKind::Rigid {
+        length: Fixed::from_f32(0.5),
+    });
+    skeleton.push_joint(Joint::new(
+        Rotation::degrees(Fixed::from(90)),
+        leg.axis_b(),
+        foot.axis_a(),
+    ));
+    skeleton.solve();
+
+    let bits = |bone: BoneId| {
+        let bone = &skeleton[bone];
+        let joint = bone.solved_joint().unwrap_or_default();
+        [
+            bone.end().x.to_bits(),
+            bone.end().y.to_bits(),
+            joint.x.to_bits(),
+            joint.y.to_bits(),
+        ]
+    };
+    assert_eq!(bits(spine), [-9, -12_884_901_909, 0, 0]);
+    assert_eq!(bits(hip), [-2_147_483_652, 3, 0, 0]);
+    assert_eq!(
+        bits(leg),
+        [-5_452_655_643, 9_080_885_415, -7_804_363_733, 3_082_998_856]
+    );
+    assert_eq!(bits(foot), [-7_451_951_163, 9_864_788_112, 0, 0]);
+}
@@ -0,0 +1,1900 @@
+//! Kinematics types that are generic over the [`Scalar`] type used for their
+//! measurements.
+//!
+//! Each type's scalar defaults to `f32`, and the types exported from the root
+//! of this crate are aliases of these types using `f32`.
+
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    string::String,
+    sync::Arc,
+    vec,
+    vec::Vec,
+};
+use core::{
+    borrow::Borrow,
+    fmt::{Debug, Display},
+    ops::{Add, AddAssign, Deref, Div, Index, IndexMut, Mul, Neg, Sub, SubAssign},
+};
+
+use crate::{
+    hierarchy::HierarchyStep, AttachmentId, AttachmentPoint, BoneAxis, BoneEnd, BoneId,
+    BoneSegment, CoordinateSystem, Hierarchy, IdMap, JointId, ReachLimit, Scalar, SocketId,
+};
+
+/// A two dimensionsional offset/measurement.
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub struct Coordinate<T: Scalar = f32> {
+    /// The x-axis component of this vector.
+    pub x: T,
+    /// The y-axis component of this vector.
+    pub y: T,
+}
+
+impl<T: Scalar> Coordinate<T> {
+    /// Returns a new vector from the x and y values.
+    #[must_use]
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+
+    /// Returns the magnitude of this vector.
+    #[must_use]
+    pub fn magnitude(&self) -> T {
+        (self.x * self.x + self.y * self.y).sqrt()
+    }
+
+    /// Returns the result of mapping `x` and `y` to `f`.
+    #[must_use]
+    pub fn map(self, mut f: impl FnMut(T) -> T) -> Self {
+        Self {
+            x: f(self.x),
+            y: f(self.y),
+        }
+    }
+
+    /// Returns the angle formed a line passing through 0,0 towards this vector.
+    #[must_use]
+    pub fn as_rotation(self) -> Rotation<T> {
+        Rotation::radians(self.y.atan2(self.x))
+    }
+
+    /// Returns a vector pointing from `self` to `other`.
+    #[must_use]
+    pub fn vector_to(self, other: Coordinate<T>) -> Vector<T> {
+        Vector::from(other - self)
+    }
+}
+
+impl<T: Scalar> Add for Coordinate<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+        }
+    }
+}
+
+impl<T: Scalar> Sub for Coordinate<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+        }
+    }
+}
+
+impl<T: Scalar> Mul<T> for Coordinate<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Self {
+            x: self.x * rhs,
+            y: self.y * rhs,
+        }
+    }
+}
+
+impl<T: Scalar> Div<T> for Coordinate<T> {
+    type Output = Self;
+
+    fn div(self, rhs: T) -> Self::Output {
+        Self {
+            x: self.x / rhs,
+            y: self.y / rhs,
+        }
+    }
+}
+
+/// A value representing a rotation in 2d space.
+///
+/// When used with a [`Skeleton`], positive rotations turn in the direction of
+/// the skeleton's [`CoordinateSystem::winding`].
+#[derive(Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub struct Rotation<T: Scalar = f32> {
+    pub(crate) radians: T,
+}
+
+impl<T: Scalar> Rotation<T> {
+    /// Returns a rotation representing the given radians.
+    #[must_use]
+    pub const fn radians(radians: T) -> Self {
+        Self { radians }
+    }
+
+    /// Returns a rotation representing the given degrees.
+    #[must_use]
+    pub fn degrees(degrees: T) -> Self {
+        Self::radians(degrees * T::PI / T::from_f32(180.))
+    }
+
+    /// Returns this rotation represented in degrees.
+    #[must_use]
+    pub fn to_degrees(self) -> T {
+        self.radians * T::from_f32(180.) / T::PI
+    }
+
+    /// Returns this rotation represented in radians.
+    #[must_use]
+    pub const fn to_radians(self) -> T {
+        self.radians
+    }
+
+    /// Returns this angle constrained between 0 and `2π`.
+    #[must_use]
+    pub fn clamped(mut self) -> Self {
+        let two_pi = T::PI + T::PI;
+        while self.radians >= two_pi {
+            self.radians -= two_pi;
+        }
+        while self.radians < T::ZERO {
+            self.radians += two_pi;
+        }
+        self
+    }
+
+    /// Returns the cosine of this angle.
+    #[must_use]
+    pub fn cos(self) -> T {
+        self.radians.cos()
+    }
+
+    /// Returns the sine of this angle.
+    #[must_use]
+    pub fn sin(self) -> T {
+        self.radians.sin()
+    }
+}
+
+impl<T: Scalar> Debug for Rotation<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl<T: Scalar> Display for Rotation<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}°", self.to_degrees())
+    }
+}
+
+impl<T: Scalar> Default for Rotation<T> {
+    fn default() -> Self {
+        Self { radians: T::ZERO }
+    }
+}
+
+impl<T: Scalar> Add for Rotation<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::radians(self.radians + rhs.radians)
+    }
+}
+
+impl<T: Scalar> AddAssign for Rotation<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.radians = (*self + rhs).radians;
+    }
+}
+
+impl<T: Scalar> Sub for Rotation<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::radians(self.radians - rhs.radians)
+    }
+}
+
+impl<T: Scalar> SubAssign for Rotation<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.radians = (*self - rhs).radians;
+    }
+}
+
+impl<T: Scalar> Neg for Rotation<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::radians(-self.radians)
+    }
+}
+
+/// A 2D Euclidean vector.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub struct Vector<T: Scalar = f32> {
+    /// The length of the vector.
+    pub magnitude: T,
+    /// The direction the vector is heading.
+    pub direction: Rotation<T>,
+}
+
+impl<T: Scalar> Vector<T> {
+    /// Returns a new vector for the given magnitude and direction.
+    #[must_use]
+    pub const fn new(magnitude: T, direction: Rotation<T>) -> Self {
+        Self {
+            magnitude,
+            direction,
+        }
+    }
+}
+
+impl<T: Scalar> From<Vector<T>> for Coordinate<T> {
+    fn from(vec: Vector<T>) -> Self {
+        Self {
+            x: vec.magnitude * vec.direction.cos(),
+            y: vec.magnitude * vec.direction.sin(),
+        }
+    }
+}
+
+impl<T: Scalar> From<Coordinate<T>> for Vector<T> {
+    fn from(pt: Coordinate<T>) -> Self {
+        Self {
+            direction: pt.as_rotation(),
+            magnitude: pt.magnitude(),
+        }
+    }
+}
+
+impl<T: Scalar> Add for Vector<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Vector<T>) -> Self::Output {
+        Vector::from(Coordinate::from(self) + Coordinate::from(rhs))
+    }
+}
+
+impl<T: Scalar> Add<Vector<T>> for Coordinate<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Vector<T>) -> Self::Output {
+        self + Coordinate::from(rhs)
+    }
+}
+
+impl<T: Scalar> Sub<Vector<T>> for Coordinate<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Vector<T>) -> Self::Output {
+        self - Coordinate::from(rhs)
+    }
+}
+
+impl<T: Scalar> Add<Rotation<T>> for Vector<T> {
+    type Output = Self;
+
+    fn add(mut self, rhs: Rotation<T>) -> Self::Output {
+        self.direction += rhs;
+        self
+    }
+}
+
+impl<T: Scalar> Sub<Rotation<T>> for Vector<T> {
+    type Output = Self;
+
+    fn sub(mut self, rhs: Rotation<T>) -> Self::Output {
+        self.direction -= rhs;
+        self
+    }
+}
+
+/// A representation of a bone structure inside of a [`Skeleton`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub enum BoneKind<T: Scalar = f32> {
+    /// A single bone of a fixed length.
+    Rigid {
+        /// The length of the bone.
+        length: T,
+    },
+    /// Two bones connected with a joint that automatically adjusts its angle as
+    /// needed.
+    Jointed {
+        /// The length of the bone connected closest to the root of the
+        /// skeleton.
+        start_length: T,
+        /// The length of the bone connected furthes from the root of the
+        /// skeleton.
+        end_length: T,
+        /// The bend of the simulated joint always goes in one of two
+        /// directions. This boolean toggles which direction the bend goes in.
+        ///
+        /// While the bone has a [pole](Bone::set_pole) set, this is only used
+        /// when the pole is directly in line with the bone.
+        inverse: bool,
+    },
+}
+
+impl<T: Scalar> BoneKind<T> {
+    /// Attaches a label to this bone when pushed into a skeleton.
+    #[must_use]
+    pub fn with_label(self, label: impl Into<String>) -> LabeledBoneKind<T> {
+        LabeledBoneKind {
+            kind: self,
+            label: label.into(),
+        }
+    }
+
+    /// Returns the full length of this bone.
+    #[must_use]
+    pub fn full_length(&self) -> T {
+        match self {
+            BoneKind::Rigid { length } => *length,
+            BoneKind::Jointed {
+                start_length,
+                end_length,
+                ..
+            } => *start_length + *end_length,
+        }
+    }
+
+    /// Returns true if this is a jointed bone that bends inversely.
+    #[must_use]
+    pub fn is_inverse(&self) -> bool {
+        match self {
+            BoneKind::Rigid { .. } => false,
+            BoneKind::Jointed { inverse, .. } => *inverse,
+        }
+    }
+
+    /// Sets whether to bend inversely.
+    ///
+    /// This function only affects a jointed bone kind.
+    pub fn set_inverse(&mut self, new_inverse: bool) {
+        let BoneKind::Jointed { inverse, .. } = self else {
+            return;
+        };
+        *inverse = new_inverse;
+    }
+}
+
+/// Settings controlling how a [`BoneKind::Jointed`] bone behaves as its
+/// desired end approaches or exceeds the bone's full length.
+///
+/// By default, the desired end is clamped to the bone's full length. This
+/// causes the simulated joint to quickly straighten as the desired end nears
+/// full extension, which can look like the joint is popping into place.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub struct SoftIk<T: Scalar = f32> {
+    /// The distance from full extension at which the bone begins easing
+    /// towards being straight. When `0.`, no easing is performed.
+    pub softness: T,
+    /// When true, both segments of the bone are lengthened as needed for the
+    /// end of the bone to reach its desired end instead of being clamped.
+    pub stretch: bool,
+}
+
+impl<T: Scalar> SoftIk<T> {
+    /// Returns settings that ease the bone into full extension over the last
+    /// `softness` units of its length.
+    #[must_use]
+    pub const fn new(softness: T) -> Self {
+        Self {
+            softness,
+            stretch: false,
+        }
+    }
+
+    /// Enables stretching the bone's segments to reach the desired end and
+    /// returns self.
+    #[must_use]
+    pub const fn stretching(mut self) -> Self {
+        self.stretch = true;
+        self
+    }
+
+    /// Returns the distance between the start and end of a bone of
+    /// `full_length` aimed at a point `distance` away, and the scale to apply
+    /// to the bone's segments.
+    fn reach(self, distance: T, full_length: T) -> (T, T) {
+        let softness = self.softness.clamp(T::ZERO, full_length);
+        let soft_start = full_length - softness;
+        let reached = if softness > T::ZERO && distance > soft_start {
+            soft_start + softness * (T::ONE - (-(distance - soft_start) / softness).exp())
+        } else {
+            distance.min(full_length)
+        };
+
+        if self.stretch && distance > reached {
+            (distance, distance / reached)
+        } else {
+            (reached, T::ONE)
+        }
+    }
+}
+
+/// A [`BoneKind`] with an associated label.
+pub struct LabeledBoneKind<T: Scalar = f32> {
+    /// The bone to create.
+    pub kind: BoneKind<T>,
+    /// The label of the bone.
+    pub label: String,
+}
+
+impl<T: Scalar> From<BoneKind<T>> for LabeledBoneKind<T> {
+    fn from(kind: BoneKind<T>) -> Self {
+        kind.with_label(String::new())
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub(crate) struct ArcString(pub(crate) Arc<String>);
+
+impl PartialEq<str> for ArcString {
+    fn eq(&self, other: &str) -> bool {
+        &**self == other
+    }
+}
+
+impl Deref for ArcString {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Borrow<str> for ArcString {
+    fn borrow(&self) -> &str {
+        self
+    }
+}
+
+/// A collection of [`Bone`]s. connected by [`Joint`]s.
+#[derive(Debug, PartialEq)]
+pub struct Skeleton<T: Scalar = f32> {
+    pub(crate) bones: Vec<Bone<T>>,
+    pub(crate) rotation: Rotation<T>,
+    pub(crate) scale: T,
+    pub(crate) flip_horizontal: bool,
+    pub(crate) flip_vertical: bool,
+    pub(crate) coordinate_system: CoordinateSystem,
+    pub(crate) joints: Vec<Joint<T>>,
+    pub(crate) connections: BTreeMap<BoneAxis, Vec<JointId>>,
+    pub(crate) generation: usize,
+    pub(crate) bones_by_label: BTreeMap<ArcString, BoneId>,
+    pub(crate) joints_by_label: BTreeMap<ArcString, JointId>,
+    pub(crate) sockets: Vec<Socket<T>>,
+    pub(crate) sockets_by_label: BTreeMap<ArcString, SocketId>,
+    pub(crate) attachments: Vec<Option<Attachment<T>>>,
+}
+
+impl<T: Scalar> Default for Skeleton<T> {
+    fn default() -> Self {
+        Self {
+            bones: Vec::new(),
+            rotation: Rotation::default(),
+            scale: T::ONE,
+            flip_horizontal: false,
+            flip_vertical: false,
+            coordinate_system: CoordinateSystem::default(),
+            joints: Vec::new(),
+            connections: BTreeMap::new(),
+            generation: 0,
+            bones_by_label: BTreeMap::new(),
+            joints_by_label: BTreeMap::new(),
+            sockets: Vec::new(),
+            sockets_by_label: BTreeMap::new(),
+            attachments: Vec::new(),
+        }
+    }
+}
+
+impl<T: Scalar> Skeleton<T> {
+    /// Creates a new [`Bone`] into the skeleton. Returns the unique id of the
+    /// created bone.
+    ///
+    /// The first bone pushed is considered the root of the skeleton. All other
+    /// bones must be connected to the root directly or indirectly through
+    /// [`Joint`]s.
+    pub fn push_bone(&mut self, bone: impl Into<LabeledBoneKind<T>>) -> BoneId {
+        let bone = bone.into();
+        let id = BoneId(u16::try_from(self.bones.len()).expect("too many bones"));
+
+        let label = if bone.label.is_empty() {
+            None
+        } else {
+            let label = ArcString(Arc::new(bone.label));
+            self.bones_by_label.insert(label.clone(), id);
+            Some(label)
+        };
+        self.bones.push(Bone {
+            id,
+            generation: self.generation,
+            label,
+            kind: bone.kind,
+            start: Coordinate::default(),
+            joint_pos: None,
+            end: Coordinate::default(),
+            desired_end: None,
+            pole: None,
+            soft_ik: SoftIk::default(),
+            entry_angle: Rotation::default(),
+            final_vector: Vector::default(),
+        });
+        id
+    }
+
+    /// Returns the list of bones in this skeleton.
+    #[must_use]
+    pub fn bones(&self) -> &[Bone<T>] {
+        &self.bones
+    }
+
+    /// Returns the list of joints in this skeleton.
+    #[must_use]
+    pub fn joints(&self) -> &[Joint<T>] {
+        &self.joints
+    }
+
+    /// Returns the bone for a given id, or none if not found.
+    #[must_use]
+    pub fn bone(&self, id: BoneId) -> Option<&Bone<T>> {
+        self.bones.get(id.index())
+    }
+
+    /// Returns an exclusive reference to the bone for a given id, or none if
+    /// not found.
+    #[must_use]
+    pub fn bone_mut(&mut self, id: BoneId) -> Option<&mut Bone<T>> {
+        self.bones.get_mut(id.index())
+    }
+
+    /// Returns the joint for a given id, or none if not found.
+    #[must_use]
+    pub fn joint(&self, id: JointId) -> Option<&Joint<T>> {
+        self.joints.get(id.index())
+    }
+
+    /// Returns an exclusive reference to the joint for a given id, or none if
+    /// not found.
+    #[must_use]
+    pub fn joint_mut(&mut self, id: JointId) -> Option<&mut Joint<T>> {
+        self.joints.get_mut(id.index())
+    }
+
+    /// Returns a list of joints connected to a specific bone axis.
+    #[must_use]
+    pub fn connections_to(&self, axis: BoneAxis) -> Option<&[JointId]> {
+        self.connections.get(&axis).map(Vec::as_slice)
+    }
+
+    /// Creates a new [`Joint`] in the skeleton, connecting two bones together
+    /// by their [axis](BoneAxis). Returns the unique id of the created joint.
+    pub fn push_joint(&mut self, mut joint: Joint<T>) -> JointId {
+        let id = JointId(u16::try_from(self.joints.len()).expect("too many joints"));
+        joint.id = id;
+        let bone_a = joint.bone_a;
+        let bone_b = joint.bone_b;
+        if let Some(label) = joint.label.clone() {
+            self.joints_by_label.insert(label, id);
+        }
+        self.joints.push(joint);
+        self.connections.entry(bone_a).or_default().push(id);
+        if bone_a != bone_b {
+            self.connections.entry(bone_b).or_default().push(id);
+        }
+        id
+    }
+
+    /// Finds an existing [`Joint`] by its label.
+    #[must_use]
+    pub fn find_joint_by_label(&self, label: &str) -> Option<JointId> {
+        self.joints_by_label.get(label).copied()
+    }
+
+    /// Finds an existing [`Bone`] by its label.
+    #[must_use]
+    pub fn find_bone_by_label(&self, label: &str) -> Option<BoneId> {
+        self.bones_by_label.get(label).copied()
+    }
+
+    /// Creates a new [`Socket`] in the skeleton. Returns the unique id of the
+    /// created socket.
+    pub fn push_socket(&mut self, mut socket: Socket<T>) -> SocketId {
+        let id = SocketId(u16::try_from(self.sockets.len()).expect("too many sockets"));
+        socket.id = id;
+        if let Some(label) = socket.label.clone() {
+            self.sockets_by_label.insert(label, id);
+        }
+        self.sockets.push(socket);
+        id
+    }
+
+    /// Returns the list of sockets in this skeleton.
+    #[must_use]
+    pub fn sockets(&self) -> &[Socket<T>] {
+        &self.sockets
+    }
+
+    /// Returns the socket for a given id, or none if not found.
+    #[must_use]
+    pub fn socket(&self, id: SocketId) -> Option<&Socket<T>> {
+        self.sockets.get(id.index())
+    }
+
+    /// Returns an exclusive reference to the socket for a given id, or none if
+    /// not found.
+    #[must_use]
+    pub fn socket_mut(&mut self, id: SocketId) -> Option<&mut Socket<T>> {
+        self.sockets.get_mut(id.index())
+    }
+
+    /// Finds an existing [`Socket`] by its label.
+    #[must_use]
+    pub fn find_socket_by_label(&self, label: &str) -> Option<SocketId> {
+        self.sockets_by_label.get(label).copied()
+    }
+
+    /// Returns a copy of the bones, joints, and sockets hanging off of
+    /// `axis`.
+    ///
+    /// The subtree contains every bone reachable by following the joints
+    /// connected to `axis` away from `axis.bone`, in the same manner as
+    /// [`Self::solve`]. `axis.bone` is not part of the subtree.
+    #[must_use]
+    pub fn clone_subtree(&self, axis: BoneAxis) -> Subtree<T> {
+        let mut subtree = Subtree {
+            root: axis,
+            bones: Vec::new(),
+            joints: Vec::new(),
+            sockets: Vec::new(),
+        };
+        let mut visited = BTreeSet::new();
+        visited.insert(axis.bone);
+        let mut to_visit = vec![axis];
+        while let Some(axis) = to_visit.pop() {
+            let Some(connections) = self.connections.get(&axis) else {
+                continue;
+            };
+            for joint_id in connections {
+                let joint = &self.joints[joint_id.index()];
+                let other_axis = joint.other_axis(axis);
+                if !visited.insert(other_axis.bone) {
+                    continue;
+                }
+                subtree.joints.push(joint.clone());
+                subtree
+                    .bones
+                    .push(self.bones[other_axis.bone.index()].clone());
+                to_visit.push(other_axis.inverse());
+            }
+        }
+        subtree.sockets = self
+            .sockets
+            .iter()
+            .filter(|socket| visited.contains(&socket.bone) && socket.bone != axis.bone)
+            .cloned()
+            .collect();
+        subtree
+    }
+
+    /// Inserts a copy of `subtree` into this skeleton, connecting it to
+    /// `attach_to` in place of the axis it was cloned from. Returns a map of
+    /// the ids in `subtree` to the ids created in this skeleton.
+    ///
+    /// Each non-empty label in `subtree` is passed through `rename` to produce
+    /// the label of the inserted copy. If a label is already in use, lookups
+    /// by that label will find the inserted copy.
+    pub fn insert_subtree(
+        &mut self,
+        subtree: &Subtree<T>,
+        attach_to: BoneAxis,
+        mut rename: impl FnMut(&str) -> String,
+    ) -> IdMap {
+        let mut rename = |label: &Option<ArcString>| {
+            label
+                .as_ref()
+                .map_or_else(String::new, |label| rename(label))
+        };
+        let mut ids = IdMap::default();
+        for bone in &subtree.bones {
+            let id = self.push_bone(bone.kind.with_label(rename(&bone.label)));
+            let new_bone = &mut self.bones[id.index()];
+            new_bone.desired_end = bone.desired_end;
+            new_bone.pole = bone.pole;
+            new_bone.soft_ik = bone.soft_ik;
+            ids.bones.insert(bone.id, id);
+        }
+        let map_axis = |axis: BoneAxis| {
+            if axis == subtree.root {
+                attach_to
+            } else {
+                BoneAxis {
+                    bone: ids.bones[&axis.bone],
+                    end: axis.end,
+                }
+            }
+        };
+        for joint in &subtree.joints {
+            let new_joint = Joint::new(joint.angle, map_axis(joint.bone_a), map_axis(joint.bone_b))
+                .with_label(rename(&joint.label));
+            let id = self.push_joint(new_joint);
+            ids.joints.insert(joint.id, id);
+        }
+        for socket in &subtree.sockets {
+            let new_socket = Socket::new(ids.bones[&socket.bone], socket.segment, socket.fraction)
+                .with_offset(socket.offset)
+                .with_label(rename(&socket.label));
+            let id = self.push_socket(new_socket);
+            ids.sockets.insert(socket.id, id);
+        }
+        ids
+    }
+
+    /// Copies the bones, joints, and sockets hanging off of `from` and
+    /// connects the copy to `attach_to`. Returns a map of the copied ids to
+    /// the newly created ids.
+    ///
+    /// This is equivalent to passing the result of [`Self::clone_subtree`] to
+    /// [`Self::insert_subtree`].
+    pub fn duplicate_subtree(
+        &mut self,
+        from: BoneAxis,
+        attach_to: BoneAxis,
+        rename: impl FnMut(&str) -> String,
+    ) -> IdMap {
+        let subtree = self.clone_subtree(from);
+        self.insert_subtree(&subtree, attach_to, rename)
+    }
+
+    /// Attaches `child` to this skeleton at `point`. Returns the unique id of
+    /// the attachment.
+    ///
+    /// Each time this skeleton is solved, the child's translation is set to
+    /// the solved position of `point`, and its rotation is set to the
+    /// direction of `point` plus the rotation `child` had when it was
+    /// attached. The child's scale and flips are combined with this
+    /// skeleton's, and the child uses this skeleton's [`CoordinateSystem`].
+    /// After being positioned, the child is solved.
+    pub fn attach(&mut self, child: Skeleton<T>, point: AttachmentPoint) -> AttachmentId {
+        let id = AttachmentId(u16::try_from(self.attachments.len()).expect("too many attachments"));
+        self.attachments.push(Some(Attachment {
+            id,
+            point,
+            rotation: child.rotation,
+            scale: child.scale,
+            flip_horizontal: child.flip_horizontal,
+            flip_vertical: child.flip_vertical,
+            skeleton: child,
+        }));
+        id
+    }
+
+    /// Detaches and returns the skeleton attached as `id`.
+    ///
+    /// The returned skeleton keeps the translation, rotation, scale, and
+    /// flips it was last solved with, which preserves its world position.
+    pub fn detach(&mut self, id: AttachmentId) -> Option<Skeleton<T>> {
+        self.attachments
+            .get_mut(id.index())?
+            .take()
+            .map(|attachment| attachment.skeleton)
+    }
+
+    /// Returns an iterator over the skeletons attached to this skeleton.
+    pub fn attachments(&self) -> impl Iterator<Item = &Attachment<T>> {
+        self.attachments.iter().flatten()
+    }
+
+    /// Returns the attachment for a given id, or none if not found.
+    #[must_use]
+    pub fn attachment(&self, id: AttachmentId) -> Option<&Attachment<T>> {
+        self.attachments.get(id.index())?.as_ref()
+    }
+
+    /// Returns an exclusive reference to the attachment for a given id, or
+    /// none if not found.
+    #[must_use]
+    pub fn attachment_mut(&mut self, id: AttachmentId) -> Option<&mut Attachment<T>> {
+        self.attachments.get_mut(id.index())?.as_mut()
+    }
+
+    /// Sets a translation to be applied to the entire skeleton.
+    pub fn set_translation(&mut self, translation: Coordinate<T>) {
+        let bone = self.bones.first_mut().expect("root bone must be defined");
+        bone.start = translation;
+    }
+
+    /// Returns the translation applied to the entire skeleton.
+    #[must_use]
+    pub fn translation(&self) -> Coordinate<T> {
+        self.bones.first().expect("root bone must be defined").start
+    }
+
+    /// Sets a base rotation to apply to the entire skeleton.
+    pub fn set_rotation(&mut self, rotation: Rotation<T>) {
+        self.rotation = rotation;
+    }
+
+    /// Returns the base rotation being applied to the entire skeleton.
+    #[must_use]
+    pub const fn rotation(&self) -> Rotation<T> {
+        self.rotation
+    }
+
+    /// Sets a uniform scale to apply to the entire skeleton.
+    ///
+    /// Bone lengths and desired ends are measured in the skeleton's local
+    /// space. The distances between solved positions are multiplied by
+    /// `scale`.
+    pub fn set_scale(&mut self, scale: T) {
+        self.scale = scale;
+    }
+
+    /// Returns the uniform scale being applied to the entire skeleton.
+    #[must_use]
+    pub const fn scale(&self) -> T {
+        self.scale
+    }
+
+    /// Sets whether the skeleton is mirrored horizontally around its
+    /// translation.
+    ///
+    /// Joint angles and desired ends are interpreted before the skeleton is
+    /// flipped, which allows the same pose to face either direction.
+    pub fn set_flip_horizontal(&mut self, flip: bool) {
+        self.flip_horizontal = flip;
+    }
+
+    /// Returns true if the skeleton is mirrored horizontally around its
+    /// translation.
+    #[must_use]
+    pub const fn flip_horizontal(&self) -> bool {
+        self.flip_horizontal
+    }
+
+    /// Sets whether the skeleton is mirrored vertically around its
+    /// translation.
+    ///
+    /// Joint angles and desired ends are interpreted before the skeleton is
+    /// flipped, which allows the same pose to face either direction.
+    pub fn set_flip_vertical(&mut self, flip: bool) {
+        self.flip_vertical = flip;
+    }
+
+    /// Returns true if the skeleton is mirrored vertically around its
+    /// translation.
+    #[must_use]
+    pub const fn flip_vertical(&self) -> bool {
+        self.flip_vertical
+    }
+
+    /// Returns the world-space offset of `vector`, which is measured in this
+    /// skeleton's local space.
+    ///
+    /// This function applies this skeleton's scale, flips, and
+    /// [`CoordinateSystem`], and can be used to convert a bone's desired end
+    /// into an offset from the bone's start.
+    #[must_use]
+    pub fn to_world_offset(&self, vector: Vector<T>) -> Coordinate<T> {
+        self.local_space().to_world(Coordinate::from(vector))
+    }
+
+    /// Returns the local-space vector of the world-space `offset`.
+    ///
+    /// This is the inverse of [`Self::to_world_offset`].
+    #[must_use]
+    pub fn to_local_vector(&self, offset: Coordinate<T>) -> Vector<T> {
+        Vector::from(self.local_space().to_local(offset))
+    }
+
+    fn local_space(&self) -> LocalSpace<T> {
+        LocalSpace {
+            scale: self.scale,
+            flip_horizontal: self.flip_horizontal,
+            flip_vertical: self.flip_vertical,
+            system: self.coordinate_system,
+        }
+    }
+
+    /// Sets the conventions used to interpret this skeleton's world-space
+    /// positions and rotations.
+    ///
+    /// The translation of the skeleton and all solved positions are measured
+    /// using `system`'s axes, and all rotations turn in `system`'s winding.
+    pub fn set_coordinate_system(&mut self, system: CoordinateSystem) {
+        self.coordinate_system = system;
+    }
+
+    /// Returns the conventions used to interpret this skeleton's world-space
+    /// positions and rotations.
+    #[must_use]
+    pub const fn coordinate_system(&self) -> CoordinateSystem {
+        self.coordinate_system
+    }
+
+    /// Updates the solved positions of all bones in this skeleton that are
+    /// connected either directly or indirectly to the root bone via [`Joint`]s.
+    pub fn solve(&mut self) {
+        self.solve_inner(None);
+    }
+
+    /// Updates the solved positions of all bones in this skeleton, returning
+    /// a report of how well each bone aimed using inverse kinematics was able
+    /// to reach its desired end.
+    pub fn solve_with_report(&mut self) -> SolveReport<T> {
+        let mut report = SolveReport::default();
+        self.solve_inner(Some(&mut report));
+        report
+    }
+
+    fn solve_inner(&mut self, report: Option<&mut SolveReport<T>>) {
+        if !self.bones.is_empty() {
+            self.generation = self.generation.wrapping_add(1);
+            self.solve_axis(report);
+        }
+    }
+
+    fn solve_axis(&mut self, mut report: Option<&mut SolveReport<T>>) {
+        // Bones are solved in the skeleton's local space, where the root bone
+        // starts at the origin and positive rotations turn from the positive x
+        // axis towards the positive y axis. Solved positions are converted to
+        // world space once all bones are solved.
+        let space = self.local_space();
+        let steps = self.walk();
+        let root_bone = &mut self.bones[0];
+        root_bone.generation = self.generation;
+        let translation = core::mem::take(&mut root_bone.start);
+        let SolvedPosition {
+            end,
+            joint: mid,
+            report: bone_report,
+            ..
+        } = determine_end_position(root_bone, self.rotation, Rotation::radians(T::ZERO));
+        root_bone.entry_angle = self.rotation;
+        root_bone.end = end;
+        root_bone.joint_pos = mid;
+        root_bone.final_vector = root_bone.solved_final_vector();
+        if let (Some(report), Some(bone_report)) = (report.as_deref_mut(), bone_report) {
+            report.bones.push(bone_report);
+        }
+
+        for HierarchyStep {
+            joint: joint_id,
+            from,
+            to,
+        } in steps
+        {
+            let parent = &self.bones[from.bone.index()];
+            let (current_position, current_rotation) = if parent.is_root() && from.end == BoneEnd::A
+            {
+                (
+                    parent.start,
+                    parent.final_vector.direction + Rotation::radians(T::PI),
+                )
+            } else {
+                (parent.end, parent.final_vector.direction)
+            };
+            let joint = &mut self.joints[joint_id.index()];
+            let bone = &mut self.bones[to.bone.index()];
+            bone.generation = self.generation;
+            bone.entry_angle = current_rotation;
+            bone.start = current_position;
+            joint.calculated_position = translation + space.to_world(current_position);
+
+            let SolvedPosition {
+                end,
+                joint: mid,
+                angle_offset,
+                report: bone_report,
+            } = determine_end_position(bone, current_rotation, joint.angle);
+            bone.entry_angle += angle_offset;
+            bone.end = end;
+            bone.joint_pos = mid;
+            bone.final_vector = bone.solved_final_vector();
+            if let (Some(report), Some(bone_report)) = (report.as_deref_mut(), bone_report) {
+                report.bones.push(bone_report);
+            }
+        }
+
+        self.place_in_world(translation);
+    }
+
+    /// Returns each joint followed when walking outward from the root bone, in
+    /// the order bones are solved.
+    fn walk(&self) -> Vec<HierarchyStep> {
+        let mut steps = Vec::new();
+        let Some(root) = self.bones.first() else {
+            return steps;
+        };
+        let mut visited = BTreeSet::new();
+        visited.insert(root.id);
+        let mut axis_visited = BTreeSet::new();
+        let mut to_visit = vec![root.id.axis_a(), root.id.axis_b()];
+        while let Some(axis) = to_visit.pop() {
+            if !axis_visited.insert(axis) {
+                continue;
+            }
+
+            let Some(connections) = self.connections.get(&axis) else {
+                continue;
+            };
+
+            for joint_id in connections {
+                let other_axis = self.joints[joint_id.index()].other_axis(axis);
+                if !visited.insert(other_axis.bone) {
+                    // We store connections in both directions, which means we
+                    // can visit bones twice. We want to ensure we only follow
+                    // each bone a single time.
+                    continue;
+                }
+                steps.push(HierarchyStep {
+                    joint: *joint_id,
+                    from: axis,
+                    to: other_axis,
+                });
+                to_visit.push(other_axis.inverse());
+            }
+        }
+        steps
+    }
+
+    /// Returns the parent-child relationships between the bones of this
+    /// skeleton, relative to the root bone.
+    #[must_use]
+    pub fn hierarchy(&self) -> Hierarchy {
+        Hierarchy::new(self.bones.len(), &self.walk())
+    }
+
+    /// Converts the local-space results of solving into world space,
+    /// updating sockets and attached skeletons along the way.
+    fn place_in_world(&mut self, translation: Coordinate<T>) {
+        let space = self.local_space();
+        let generation = self.generation;
+        let solved = |bone: &&Bone<T>| bone.generation == generation;
+        for socket in &mut self.sockets {
+            let Some(bone) = self.bones.get(socket.bone.index()).filter(solved) else {
+                continue;
+            };
+            let (position, direction) = bone.socket_frame(socket);
+            socket.position = translation + space.to_world(position);
+            socket.rotation = space.rotation_to_world(direction);
+        }
+
+        for attachment in self.attachments.iter_mut().flatten() {
+            let frame = match attachment.point {
+                AttachmentPoint::Axis(axis) => self
+                    .bones
+                    .get(axis.bone.index())
+                    .filter(solved)
+                    .map(|bone| bone.axis_frame(axis.end)),
+                AttachmentPoint::Socket(socket) => {
+                    self.sockets.get(socket.index()).and_then(|socket| {
+                        self.bones
+                            .get(socket.bone.index())
+                            .filter(solved)
+                            .map(|bone| bone.socket_frame(socket))
+                    })
+                }
+            };
+            let child = &mut attachment.skeleton;
+            if let Some((position, direction)) = frame {
+                if let Some(root) = child.bones.first_mut() {
+                    root.start = translation + space.to_world(position);
+                }
+                child.rotation = direction + attachment.rotation;
+            }
+            child.scale = self.scale * attachment.scale;
+            child.flip_horizontal = self.flip_horizontal ^ attachment.flip_horizontal;
+            child.flip_vertical = self.flip_vertical ^ attachment.flip_vertical;
+            child.coordinate_system = self.coordinate_system;
+            child.solve();
+        }
+
+        for bone in &mut self.bones {
+            if bone.generation == self.generation {
+                bone.start = translation + space.to_world(bone.start);
+                bone.joint_pos = bone
+                    .joint_pos
+                    .map(|joint| translation + space.to_world(joint));
+                bone.end = translation + space.to_world(bone.end);
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct LocalSpace<T: Scalar> {
+    scale: T,
+    flip_horizontal: bool,
+    flip_vertical: bool,
+    system: CoordinateSystem,
+}
+
+impl<T: Scalar> LocalSpace<T> {
+    fn flip(self, mut offset: Coordinate<T>) -> Coordinate<T> {
+        if self.flip_horizontal {
+            offset.x = -offset.x;
+        }
+        if self.flip_vertical {
+            offset.y = -offset.y;
+        }
+        offset
+    }
+
+    fn to_world(self, local: Coordinate<T>) -> Coordinate<T> {
+        self.system.mirror(self.flip(local * self.scale))
+    }
+
+    fn to_local(self, world: Coordinate<T>) -> Coordinate<T> {
+        self.flip(self.system.mirror(world)) / self.scale
+    }
+
+    fn rotation_to_world(self, local: Rotation<T>) -> Rotation<T> {
+        if self.flip_horizontal || self.flip_vertical {
+            self.system
+                .to_vector(self.to_world(Coordinate::from(Vector::new(T::ONE, local))))
+                .direction
+        } else {
+            local
+        }
+    }
+}
+
+struct SolvedPosition<T: Scalar> {
+    end: Coordinate<T>,
+    joint: Option<Coordinate<T>>,
+    angle_offset: Rotation<T>,
+    report: Option<BoneReport<T>>,
+}
+
+fn determine_end_position<T: Scalar>(
+    bone: &Bone<T>,
+    current_rotation: Rotation<T>,
+    joint_angle: Rotation<T>,
+) -> SolvedPosition<T> {
+    let start = bone.start;
+    let entry_angle = current_rotation + joint_angle;
+    match &bone.kind {
+        BoneKind::Rigid { length } => SolvedPosition {
+            end: start + Vector::new(*length, entry_angle),
+            joint: None,
+            angle_offset: Rotation::default(),
+            report: None,
+        },
+        BoneKind::Jointed {
+            start_length,
+            end_length,
+            inverse,
+        } => {
+            if let Some(desired_end) = bone.desired_end {
+                let desired_angle = desired_end.direction + entry_angle;
+                let requested = desired_end.magnitude;
+                let full_length = *start_length + *end_length;
+                let minimum_size = (*start_length - *end_length).abs();
+                let distance = requested.max(minimum_size);
+                let (desired_length, scale) = bone.soft_ik.reach(distance, full_length);
+
+                let end = start + Vector::new(desired_length, desired_angle);
+
+                let inverse = bone.pole.map_or(*inverse, |pole| {
+                    // Bend towards whichever side of the line between start
+                    // and end the pole is on.
+                    let side = (pole.direction + entry_angle - desired_angle).sin();
+                    if pole.magnitude.abs() <= T::EPSILON || side.abs() <= T::EPSILON {
+                        *inverse
+                    } else {
+                        side > T::ZERO
+                    }
+                });
+                let first = *start_length * scale;
+                let joint = get_third_point(
+                    inverse,
+                    start,
+                    desired_length,
+                    desired_angle,
+                    first,
+                    *end_length * scale,
+                );
+
+                let clamped = if requested < minimum_size {
+                    Some(ReachLimit::Minimum)
+                } else if requested > full_length && !bone.soft_ik.stretch {
+                    Some(ReachLimit::Maximum)
+                } else if desired_length < distance {
+                    Some(ReachLimit::Soft)
+                } else {
+                    None
+                };
+                let report = BoneReport {
+                    bone: bone.id,
+                    requested,
+                    achieved: desired_length,
+                    clamped,
+                    joint_fallback: joint.is_none(),
+                };
+                let joint = joint.unwrap_or_else(|| start + Vector::new(first, desired_angle));
+
+                SolvedPosition {
+                    end,
+                    joint: Some(joint),
+                    angle_offset: joint_angle,
+                    report: Some(report),
+                }
+            } else {
+                let joint = start + Vector::new(*start_length, entry_angle);
+                let end = joint + Vector::new(*end_length, entry_angle);
+                SolvedPosition {
+                    end,
+                    joint: Some(joint),
+                    angle_offset: joint_angle,
+                    report: None,
+                }
+            }
+        }
+    }
+}
+
+fn get_third_point<T: Scalar>(
+    inverse: bool,
+    start: Coordinate<T>,
+    distance: T,
+    hyp_angle: Rotation<T>,
+    first: T,
+    second: T,
+) -> Option<Coordinate<T>> {
+    let hyp = distance;
+    let cos = (first * first + hyp * hyp - second * second) / ((first + first) * hyp);
+    // The law of cosines can only be solved when the cosine is in -1..=1. This
+    // check also rejects NaN.
+    if cos >= -T::ONE && cos <= T::ONE {
+        let first_angle = cos.acos();
+        let first_angle = hyp_angle
+            - Rotation {
+                radians: if inverse { -first_angle } else { first_angle },
+            };
+        Some(start + Vector::new(first, first_angle))
+    } else {
+        None
+    }
+}
+
+/// A report of how well each bone aimed using inverse kinematics reached its
+/// desired end during [`Skeleton::solve_with_report`].
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SolveReport<T: Scalar = f32> {
+    pub(crate) bones: Vec<BoneReport<T>>,
+}
+
+impl<T: Scalar> SolveReport<T> {
+    /// Returns the reports of all bones aimed using inverse kinematics, in the
+    /// order they were solved.
+    #[must_use]
+    pub fn bones(&self) -> &[BoneReport<T>] {
+        &self.bones
+    }
+
+    /// Returns the report for a specific bone, if it was aimed using inverse
+    /// kinematics.
+    #[must_use]
+    pub fn bone(&self, id: BoneId) -> Option<&BoneReport<T>> {
+        self.bones.iter().find(|report| report.bone == id)
+    }
+
+    /// Returns true if every bone was able to reach its desired end without
+    /// being clamped or needing to fall back to a straight joint.
+    #[must_use]
+    pub fn all_reached(&self) -> bool {
+        self.bones
+            .iter()
+            .all(|report| report.clamped.is_none() && !report.joint_fallback)
+    }
+}
+
+/// The result of aiming a single [`BoneKind::Jointed`] bone at its desired
+/// end.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoneReport<T: Scalar = f32> {
+    /// The bone this report is about.
+    pub bone: BoneId,
+    /// The distance between the bone's start and its desired end.
+    pub requested: T,
+    /// The distance between the bone's start and its solved end.
+    pub achieved: T,
+    /// If the desired end was outside of the range the bone can reach, or soft
+    /// IK eased the bone short of it, this contains which limit applied.
+    pub clamped: Option<ReachLimit>,
+    /// True if the position of the bone's joint could not be calculated and
+    /// the joint was placed in line with the bone instead.
+    pub joint_fallback: bool,
+}
+
+impl<T: Scalar> BoneReport<T> {
+    /// Returns the difference between the requested and achieved distances.
+    ///
+    /// A positive value means the bone fell short of its desired end, while a
+    /// negative value means the bone's end is further away than desired.
+    #[must_use]
+    pub fn shortfall(&self) -> T {
+        self.requested - self.achieved
+    }
+}
+
+impl<T: Scalar> Index<SocketId> for Skeleton<T> {
+    type Output = Socket<T>;
+
+    fn index(&self, index: SocketId) -> &Self::Output {
+        &self.sockets[index.index()]
+    }
+}
+
+impl<T: Scalar> IndexMut<SocketId> for Skeleton<T> {
+    fn index_mut(&mut self, index: SocketId) -> &mut Self::Output {
+        &mut self.sockets[index.index()]
+    }
+}
+
+impl<T: Scalar> Index<BoneId> for Skeleton<T> {
+    type Output = Bone<T>;
+
+    fn index(&self, index: BoneId) -> &Self::Output {
+        &self.bones[index.index()]
+    }
+}
+
+impl<T: Scalar> IndexMut<BoneId> for Skeleton<T> {
+    fn index_mut(&mut self, index: BoneId) -> &mut Self::Output {
+        &mut self.bones[index.index()]
+    }
+}
+
+impl<T: Scalar> Index<JointId> for Skeleton<T> {
+    type Output = Joint<T>;
+
+    fn index(&self, index: JointId) -> &Self::Output {
+        &self.joints[index.index()]
+    }
+}
+
+impl<T: Scalar> IndexMut<JointId> for Skeleton<T> {
+    fn index_mut(&mut self, index: JointId) -> &mut Self::Output {
+        &mut self.joints[index.index()]
+    }
+}
+
+/// A bone in a [`Skeleton`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bone<T: Scalar = f32> {
+    pub(crate) id: BoneId,
+    pub(crate) generation: usize,
+    pub(crate) label: Option<ArcString>,
+    pub(crate) kind: BoneKind<T>,
+    pub(crate) start: Coordinate<T>,
+    pub(crate) joint_pos: Option<Coordinate<T>>,
+    pub(crate) end: Coordinate<T>,
+    pub(crate) desired_end: Option<Vector<T>>,
+    pub(crate) pole: Option<Vector<T>>,
+    pub(crate) soft_ik: SoftIk<T>,
+    pub(crate) entry_angle: Rotation<T>,
+    pub(crate) final_vector: Vector<T>,
+}
+
+impl<T: Scalar> Bone<T> {
+    /// Returns the unique id of this bone.
+    #[must_use]
+    pub const fn id(&self) -> BoneId {
+        self.id
+    }
+
+    /// Returns true if this bone is the root of the skeleton.
+    #[must_use]
+    pub const fn is_root(&self) -> bool {
+        self.id.0 == 0
+    }
+
+    /// Returns the kind of this bone.
+    #[must_use]
+    pub const fn kind(&self) -> &BoneKind<T> {
+        &self.kind
+    }
+
+    /// Returns an exclusive reference to the kind of this bone.
+    #[must_use]
+    pub fn kind_mut(&mut self) -> &mut BoneKind<T> {
+        &mut self.kind
+    }
+
+    /// Sets a relative position to aim the end of this bone towards.
+    ///
+    /// The end of the bone that is aimed is the end that is furthest from the
+    /// root of the skeleton.
+    ///
+    /// This setting only impacts [`BoneKind::Jointed`] bones.
+    pub fn set_desired_end(&mut self, end: Option<Vector<T>>) {
+        self.desired_end = end;
+    }
+
+    /// Returns the location this bone is being aimed towards.
+    #[must_use]
+    pub const fn desired_end(&self) -> Option<Vector<T>> {
+        self.desired_end
+    }
+
+    /// Sets a relative position that the joint of this bone should bend
+    /// towards.
+    ///
+    /// The pole is measured from the start of the bone in the same space as
+    /// [`Self::set_desired_end`]. When solving, the joint is placed on the
+    /// same side of the line between the bone's start and end as the pole.
+    /// While a pole is set, [`BoneKind::Jointed::inverse`] is only used when
+    /// the pole lies on that line.
+    ///
+    /// This setting only impacts [`BoneKind::Jointed`] bones.
+    pub fn set_pole(&mut self, pole: Option<Vector<T>>) {
+        self.pole = pole;
+    }
+
+    /// Returns the location this bone's joint is bending towards.
+    #[must_use]
+    pub const fn pole(&self) -> Option<Vector<T>> {
+        self.pole
+    }
+
+    /// Sets how this bone approaches and exceeds its full extension when
+    /// aiming at its desired end.
+    ///
+    /// This setting only impacts [`BoneKind::Jointed`] bones.
+    pub fn set_soft_ik(&mut self, soft_ik: SoftIk<T>) {
+        self.soft_ik = soft_ik;
+    }
+
+    /// Returns how this bone approaches and exceeds its full extension.
+    #[must_use]
+    pub const fn soft_ik(&self) -> SoftIk<T> {
+        self.soft_ik
+    }
+
+    /// Returns the angle of the previous bone segment connecting to this bone.
+    ///
+    /// This angle is measured in the skeleton's local space, before its scale
+    /// and flips are applied.
+    #[must_use]
+    pub const fn entry_angle(&self) -> Rotation<T> {
+        self.entry_angle
+    }
+
+    /// Returns the solved start position of this bone.
+    #[must_use]
+    pub const fn start(&self) -> Coordinate<T> {
+        self.start
+    }
+
+    /// Returns the solved end position of this bone.
+    #[must_use]
+    pub const fn end(&self) -> Coordinate<T> {
+        self.end
+    }
+
+    /// Returns a vector representing the final segment in this bone.
+    ///
+    /// This vector is measured in the skeleton's local space, before its scale
+    /// and flips are applied.
+    #[must_use]
+    pub const fn final_vector(&self) -> Vector<T> {
+        self.final_vector
+    }
+
+    fn solved_final_vector(&self) -> Vector<T> {
+        let start = self.joint_pos.unwrap_or(self.start);
+        start.vector_to(self.end)
+    }
+
+    /// Returns the position and direction of `socket` while this bone's
+    /// solved positions are in the skeleton's local space.
+    fn socket_frame(&self, socket: &Socket<T>) -> (Coordinate<T>, Rotation<T>) {
+        let (start, end) = match (socket.segment, self.joint_pos) {
+            (BoneSegment::Start, Some(joint)) => (self.start, joint),
+            (BoneSegment::End, Some(joint)) => (joint, self.end),
+            (_, None) => (self.start, self.end),
+        };
+        let direction = start.vector_to(end).direction;
+        let position = start + (end - start) * socket.fraction + (socket.offset + direction);
+        (position, direction)
+    }
+
+    /// Returns the position of `end` and the direction pointing away from
+    /// this bone at that end while this bone's solved positions are in the
+    /// skeleton's local space.
+    fn axis_frame(&self, end: BoneEnd) -> (Coordinate<T>, Rotation<T>) {
+        match end {
+            BoneEnd::A => {
+                let first_end = self.joint_pos.unwrap_or(self.end);
+                (self.start, first_end.vector_to(self.start).direction)
+            }
+            BoneEnd::B => (self.end, self.final_vector.direction),
+        }
+    }
+
+    /// If this is a [`BoneKind::Jointed`] bone, returns the solved position of
+    /// the joint.
+    #[must_use]
+    pub const fn solved_joint(&self) -> Option<Coordinate<T>> {
+        self.joint_pos
+    }
+
+    /// Returns the label this bone was created with.
+    #[must_use]
+    pub fn label(&self) -> &str {
+        self.label.as_ref().map_or("", |s| s)
+    }
+}
+
+/// A connection between two bones.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Joint<T: Scalar = f32> {
+    pub(crate) id: JointId,
+    pub(crate) label: Option<ArcString>,
+    pub(crate) bone_a: BoneAxis,
+    pub(crate) bone_b: BoneAxis,
+    pub(crate) calculated_position: Coordinate<T>,
+    pub(crate) angle: Rotation<T>,
+}
+
+impl<T: Scalar> Joint<T> {
+    /// Returns the unique id of this joint.
+    #[must_use]
+    pub const fn id(&self) -> JointId {
+        self.id
+    }
+
+    /// Returns a new joint formed by joining `bone_a` and `bone_b` at `angle`.
+    #[must_use]
+    pub const fn new(angle: Rotation<T>, bone_a: BoneAxis, bone_b: BoneAxis) -> Self {
+        Self {
+            id: JointId(0),
+            label: None,
+            bone_a,
+            bone_b,
+            calculated_position: Coordinate::new(T::ZERO, T::ZERO),
+            angle,
+        }
+    }
+
+    /// Labels this joint and returns self.
+    #[must_use]
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        let label = label.into();
+        if !label.is_empty() {
+            self.label = Some(ArcString(Arc::new(label)));
+        }
+        self
+    }
+
+    /// Returns the label of this joint.
+    #[must_use]
+    pub fn label(&self) -> &str {
+        self.label.as_ref().map_or("", |s| s)
+    }
+
+    /// Given `axis` is one of the two connections in this joint, return the
+    /// other axis.
+    ///
+    /// # Panics
+    ///
+    /// This function has a debug assertion that ensures that `axis` is one of
+    /// the bones in this joint.
+    #[must_use]
+    pub fn other_axis(&self, axis: BoneAxis) -> BoneAxis {
+        if self.bone_a == axis {
+            self.bone_b
+        } else {
+            debug_assert_eq!(self.bone_b, axis);
+            self.bone_a
+        }
+    }
+
+    /// Sets the angle to form between these joints.
+    ///
+    /// This setting is ignored if the bone furthest from the root of the joint
+    /// is a [`BoneKind::Jointed`] bone.
+    pub fn set_angle(&mut self, angle: Rotation<T>) {
+        self.angle = angle;
+    }
+
+    /// Returns the rotation of this joint.
+    #[must_use]
+    pub const fn angle(&self) -> Rotation<T> {
+        self.angle
+    }
+}
+
+/// A named location along a [`Bone`] that other objects can be attached to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Socket<T: Scalar = f32> {
+    pub(crate) id: SocketId,
+    pub(crate) label: Option<ArcString>,
+    pub(crate) bone: BoneId,
+    pub(crate) segment: BoneSegment,
+    pub(crate) fraction: T,
+    pub(crate) offset: Vector<T>,
+    pub(crate) position: Coordinate<T>,
+    pub(crate) rotation: Rotation<T>,
+}
+
+impl<T: Scalar> Socket<T> {
+    /// Returns a new socket located `fraction` of the way along `segment` of
+    /// `bone`.
+    ///
+    /// A `fraction` of `0.` is located at the start of the segment, and `1.`
+    /// is located at the end of the segment. For [`BoneKind::Rigid`] bones,
+    /// `segment` is ignored.
+    #[must_use]
+    pub const fn new(bone: BoneId, segment: BoneSegment, fraction: T) -> Self {
+        Self {
+            id: SocketId(0),
+            label: None,
+            bone,
+            segment,
+            fraction,
+            offset: Vector::new(T::ZERO, Rotation::radians(T::ZERO)),
+            position: Coordinate::new(T::ZERO, T::ZERO),
+            rotation: Rotation::radians(T::ZERO),
+        }
+    }
+
+    /// Labels this socket and returns self.
+    #[must_use]
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        let label = label.into();
+        if !label.is_empty() {
+            self.label = Some(ArcString(Arc::new(label)));
+        }
+        self
+    }
+
+    /// Sets the offset of this socket and returns self.
+    #[must_use]
+    pub fn with_offset(mut self, offset: Vector<T>) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Returns the unique id of this socket.
+    #[must_use]
+    pub const fn id(&self) -> SocketId {
+        self.id
+    }
+
+    /// Returns the label of this socket.
+    #[must_use]
+    pub fn label(&self) -> &str {
+        self.label.as_ref().map_or("", |s| s)
+    }
+
+    /// Returns the bone this socket is located on.
+    #[must_use]
+    pub const fn bone(&self) -> BoneId {
+        self.bone
+    }
+
+    /// Returns the segment of the bone this socket is located on.
+    #[must_use]
+    pub const fn segment(&self) -> BoneSegment {
+        self.segment
+    }
+
+    /// Sets how far along the segment this socket is located.
+    pub fn set_fraction(&mut self, fraction: T) {
+        self.fraction = fraction;
+    }
+
+    /// Returns how far along the segment this socket is located.
+    #[must_use]
+    pub const fn fraction(&self) -> T {
+        self.fraction
+    }
+
+    /// Sets the offset of this socket.
+    ///
+    /// The offset's direction is relative to the direction of the segment,
+    /// which means a direction of 90 degrees is perpendicular to the bone.
+    pub fn set_offset(&mut self, offset: Vector<T>) {
+        self.offset = offset;
+    }
+
+    /// Returns the offset of this socket.
+    #[must_use]
+    pub const fn offset(&self) -> Vector<T> {
+        self.offset
+    }
+
+    /// Returns the solved world-space position of this socket.
+    #[must_use]
+    pub const fn position(&self) -> Coordinate<T> {
+        self.position
+    }
+
+    /// Returns the solved world-space rotation of this socket, which is the
+    /// direction of the segment it is located on.
+    #[must_use]
+    pub const fn rotation(&self) -> Rotation<T> {
+        self.rotation
+    }
+}
+
+/// A copy of the bones, joints, and sockets hanging off of a [`BoneAxis`] in
+/// a [`Skeleton`].
+///
+/// Subtrees are created using [`Skeleton::clone_subtree`] and can be inserted
+/// into any skeleton using [`Skeleton::insert_subtree`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Subtree<T: Scalar = f32> {
+    root: BoneAxis,
+    bones: Vec<Bone<T>>,
+    joints: Vec<Joint<T>>,
+    sockets: Vec<Socket<T>>,
+}
+
+impl<T: Scalar> Subtree<T> {
+    /// Returns the axis this subtree was cloned from.
+    #[must_use]
+    pub const fn root(&self) -> BoneAxis {
+        self.root
+    }
+
+    /// Returns the bones in this subtree, using their original ids.
+    #[must_use]
+    pub fn bones(&self) -> &[Bone<T>] {
+        &self.bones
+    }
+
+    /// Returns the joints in this subtree, using their original ids.
+    #[must_use]
+    pub fn joints(&self) -> &[Joint<T>] {
+        &self.joints
+    }
+
+    /// Returns the sockets in this subtree, using their original ids.
+    #[must_use]
+    pub fn sockets(&self) -> &[Socket<T>] {
+        &self.sockets
+    }
+}
+
+/// A [`Skeleton`] attached to another skeleton.
+#[derive(Debug, PartialEq)]
+pub struct Attachment<T: Scalar = f32> {
+    pub(crate) id: AttachmentId,
+    pub(crate) point: AttachmentPoint,
+    pub(crate) rotation: Rotation<T>,
+    pub(crate) scale: T,
+    pub(crate) flip_horizontal: bool,
+    pub(crate) flip_vertical: bool,
+    pub(crate) skeleton: Skeleton<T>,
+}
+
+impl<T: Scalar> Attachment<T> {
+    /// Returns the unique id of this attachment.
+    #[must_use]
+    pub const fn id(&self) -> AttachmentId {
+        self.id
+    }
+
+    /// Sets the location the attached skeleton is rooted at.
+    pub fn set_point(&mut self, point: AttachmentPoint) {
+        self.point = point;
+    }
+
+    /// Returns the location the attached skeleton is rooted at.
+    #[must_use]
+    pub const fn point(&self) -> AttachmentPoint {
+        self.point
+    }
+
+    /// Sets the rotation of the attached skeleton relative to the direction
+    /// of its attachment point.
+    pub fn set_rotation(&mut self, rotation: Rotation<T>) {
+        self.rotation = rotation;
+    }
+
+    /// Returns the rotation of the attached skeleton relative to the
+    /// direction of its attachment point.
+    #[must_use]
+    pub const fn rotation(&self) -> Rotation<T> {
+        self.rotation
+    }
+
+    /// Sets the scale of the attached skeleton relative to the skeleton it is
+    /// attached to.
+    pub fn set_scale(&mut self, scale: T) {
+        self.scale = scale;
+    }
+
+    /// Returns the scale of the attached skeleton relative to the skeleton it
+    /// is attached to.
+    #[must_use]
+    pub const fn scale(&self) -> T {
+        self.scale
+    }
+
+    /// Sets whether the attached skeleton is flipped relative to the skeleton
+    /// it is attached to.
+    pub fn set_flip(&mut self, horizontal: bool, vertical: bool) {
+        self.flip_horizontal = horizontal;
+        self.flip_vertical = vertical;
+    }
+
+    /// Returns the attached skeleton.
+    #[must_use]
+    pub const fn skeleton(&self) -> &Skeleton<T> {
+        &self.skeleton
+    }
+
+    /// Returns an exclusive reference to the attached skeleton.
+    ///
+    /// The translation, rotation, scale, flips, and coordinate system of the
+    /// attached skeleton are replaced each time its parent is solved.
+    #[must_use]
+    pub fn skeleton_mut(&mut self) -> &mut Skeleton<T> {
+        &mut self.skeleton
+    }
+}
+
+#[test]
+fn f64_skeleton() {
+    let mut skeleton = Skeleton::<f64>::default();
+    let root = skeleton.push_bone(BoneKind::Rigid { length: 1. });
+    let arm = skeleton.push_bone(BoneKind::Jointed {
+        start_length: 1.,
+        end_length: 1.,
+        inverse: false,
+    });
+    skeleton.push_joint(Joint::new(
+        Rotation::degrees(90.),
+        root.axis_b(),
+        arm.axis_a(),
+    ));
+    skeleton.solve();
+
+    let end = skeleton[arm].end();
+    assert!((end.x - 1.).abs() < 1e-12, "{end:?}");
+    assert!((end.y - 2.).abs() < 1e-12, "{end:?}");
+}
+
+#[test]
+fn default_scalar() {
+    fn length(skeleton: &Skeleton, bone: crate::BoneId) -> f32 {
+        let bone: &Bone = &skeleton[bone];
+        let vector: Vector = Vector::from(bone.end() - bone.start());
+        vector.magnitude
+    }
+
+    let mut skeleton: Skeleton = Skeleton::default();
+    let root = skeleton.push_bone(BoneKind::Rigid { length: 2. });
+    skeleton.solve();
+    assert!((length(&skeleton, root) - 2.).abs() < 0.0001);
+
+    let skeleton: crate::Skeleton = skeleton;
+    assert_eq!(skeleton.bones().len(), 1);
+}
@@ -0,0 +1,207 @@
+use alloc::{
+    collections::{BTreeSet, VecDeque},
+    vec,
+    vec::Vec,
+};
+
+use crate::{BoneAxis, BoneId, JointId};
+
+/// A snapshot of the parent-child relationships between the bones of a
+/// [`Skeleton`](crate::Skeleton).
+///
+/// The hierarchy is formed by walking the skeleton's [`Joint`](crate::Joint)s
+/// outward from the root bone in the same order as
+/// [`Skeleton::solve`](crate::Skeleton::solve). Each bone's parent is the
+/// bone it is solved relative to. Bones that are not connected to the root
+/// bone are not part of the hierarchy.
+///
+/// A hierarchy is created using
+/// [`Skeleton::hierarchy`](crate::generic::Skeleton::hierarchy), and does not
+/// reflect changes made to the skeleton after it was created.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hierarchy {
+    parents: Vec<Option<(JointId, BoneAxis)>>,
+    children: Vec<Vec<(JointId, BoneId)>>,
+    connected: Vec<bool>,
+}
+
+impl Hierarchy {
+    pub(crate) fn new(bone_count: usize, steps: &[HierarchyStep]) -> Self {
+        let mut parents = vec![None; bone_count];
+        let mut children = vec![Vec::new(); bone_count];
+        let mut connected = vec![false; bone_count];
+        if let Some(root) = connected.first_mut() {
+            *root = true;
+        }
+        for step in steps {
+            parents[step.to.bone.index()] = Some((step.joint, step.from));
+            children[step.from.bone.index()].push((step.joint, step.to.bone));
+            connected[step.to.bone.index()] = true;
+        }
+        Self {
+            parents,
+            children,
+            connected,
+        }
+    }
+
+    /// Returns the root bone of the hierarchy, or `None` if the skeleton has
+    /// no bones.
+    #[must_use]
+    pub fn root(&self) -> Option<BoneId> {
+        (!self.connected.is_empty()).then_some(BoneId(0))
+    }
+
+    /// Returns true if `bone` is connected to the root bone.
+    #[must_use]
+    pub fn contains(&self, bone: BoneId) -> bool {
+        self.connected.get(bone.index()).copied().unwrap_or(false)
+    }
+
+    /// Returns the joint connecting `bone` to its parent, or `None` if `bone`
+    /// is the root or is not connected to the root.
+    #[must_use]
+    pub fn parent_joint(&self, bone: BoneId) -> Option<JointId> {
+        self.parents
+            .get(bone.index())
+            .copied()
+            .flatten()
+            .map(|(joint, _)| joint)
+    }
+
+    /// Returns the axis of the parent bone that `bone` is connected to, or
+    /// `None` if `bone` is the root or is not connected to the root.
+    #[must_use]
+    pub fn parent(&self, bone: BoneId) -> Option<BoneAxis> {
+        self.parents
+            .get(bone.index())
+            .copied()
+            .flatten()
+            .map(|(_, axis)| axis)
+    }
+
+    /// Returns an iterator over the bones whose parent is `bone`, along with
+    /// the joint connecting each child to `bone`.
+    pub fn children(&self, bone: BoneId) -> impl Iterator<Item = (JointId, BoneId)> + '_ {
+        self.children
+            .get(bone.index())
+            .into_iter()
+            .flatten()
+            .copied()
+    }
+
+    /// Returns an iterator that visits `from` and all of its descendants in
+    /// depth-first order.
+    ///
+    /// Each bone is visited before its children. If `from` is not part of the
+    /// hierarchy, the iterator is empty.
+    #[must_use]
+    pub fn depth_first(&self, from: BoneId) -> DepthFirst<'_> {
+        DepthFirst {
+            hierarchy: self,
+            stack: if self.contains(from) {
+                vec![from]
+            } else {
+                Vec::new()
+            },
+        }
+    }
+
+    /// Returns an iterator that visits `from` and all of its descendants in
+    /// breadth-first order.
+    ///
+    /// If `from` is not part of the hierarchy, the iterator is empty.
+    #[must_use]
+    pub fn breadth_first(&self, from: BoneId) -> BreadthFirst<'_> {
+        let mut queue = VecDeque::new();
+        if self.contains(from) {
+            queue.push_back(from);
+        }
+        BreadthFirst {
+            hierarchy: self,
+            queue,
+        }
+    }
+
+    /// Returns the bones between the root and `bone`, including both the root
+    /// and `bone`.
+    ///
+    /// Returns `None` if `bone` is not connected to the root.
+    #[must_use]
+    pub fn path_to(&self, bone: BoneId) -> Option<Vec<BoneId>> {
+        if !self.contains(bone) {
+            return None;
+        }
+        let mut path = vec![bone];
+        let mut current = bone;
+        while let Some(parent) = self.parent(current) {
+            path.push(parent.bone);
+            current = parent.bone;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Returns the bones whose position depends on `joint`.
+    ///
+    /// These are the bone that `joint` connects to its parent, and all of
+    /// that bone's descendants. If `joint` is not used to connect a bone to
+    /// its parent, the returned set is empty.
+    #[must_use]
+    pub fn downstream_of(&self, joint: JointId) -> BTreeSet<BoneId> {
+        self.parents
+            .iter()
+            .position(|parent| parent.is_some_and(|(parent_joint, _)| parent_joint == joint))
+            .map(|index| {
+                self.depth_first(BoneId(u16::try_from(index).expect("too many bones")))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// A single joint followed when walking a skeleton outward from its root.
+pub(crate) struct HierarchyStep {
+    pub joint: JointId,
+    pub from: BoneAxis,
+    pub to: BoneAxis,
+}
+
+/// A depth-first iterator over a [`Hierarchy`].
+#[derive(Debug, Clone)]
+pub struct DepthFirst<'a> {
+    hierarchy: &'a Hierarchy,
+    stack: Vec<BoneId>,
+}
+
+impl Iterator for DepthFirst<'_> {
+    type Item = BoneId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bone = self.stack.pop()?;
+        let start = self.stack.len();
+        self.stack
+            .extend(self.hierarchy.children(bone).map(|(_, child)| child));
+        // Reverse the children so that they are visited in order.
+        self.stack[start..].reverse();
+        Some(bone)
+    }
+}
+
+/// A breadth-first iterator over a [`Hierarchy`].
+#[derive(Debug, Clone)]
+pub struct BreadthFirst<'a> {
+    hierarchy: &'a Hierarchy,
+    queue: VecDeque<BoneId>,
+}
+
+impl Iterator for BreadthFirst<'_> {
+    type Item = BoneId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bone = self.queue.pop_front()?;
+        self.queue
+            .extend(self.hierarchy.children(bone).map(|(_, child)| child));
+        Some(bone)
+    }
+}
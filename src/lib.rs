@@ -1,111 +1,61 @@
 #![doc = include_str!(".crate-docs.md")]
+#![cfg_attr(not(feature = "std"), no_std)]
 
-use std::{
-    borrow::Borrow,
-    collections::{HashMap, HashSet},
-    f32::consts::PI,
-    fmt::{Debug, Display},
-    ops::{Add, AddAssign, Deref, Div, Index, IndexMut, Mul, Neg, Sub, SubAssign},
-    sync::Arc,
-    vec::Vec,
-};
+extern crate alloc;
+
+use alloc::collections::BTreeMap;
+use core::{f32::consts::PI, fmt::Display};
 
 pub mod animation;
 #[cfg(feature = "cushy")]
 pub mod cushy;
 #[cfg(feature = "editor")]
 pub mod editor;
+mod fixed;
+pub mod generic;
+mod hierarchy;
+mod macros;
+pub mod rigs;
+mod scalar;
 #[cfg(feature = "serde")]
 mod serde;
 
-/// A two dimensionsional offset/measurement.
-#[derive(Default, Clone, Copy, PartialEq, Debug)]
-#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
-pub struct Coordinate {
-    /// The x-axis component of this vector.
-    pub x: f32,
-    /// The y-axis component of this vector.
-    pub y: f32,
-}
-
-impl Coordinate {
-    /// Returns a new vector from the x and y values.
-    #[must_use]
-    pub const fn new(x: f32, y: f32) -> Self {
-        Self { x, y }
-    }
-
-    /// Returns the magnitude of this vector.
-    #[must_use]
-    pub fn magnitude(&self) -> f32 {
-        (self.x * self.x + self.y * self.y).sqrt()
-    }
-
-    /// Returns the result of mapping `x` and `y` to `f`.
-    #[must_use]
-    pub fn map(self, mut f: impl FnMut(f32) -> f32) -> Self {
-        Self {
-            x: f(self.x),
-            y: f(self.y),
-        }
-    }
-
-    /// Returns the angle formed a line passing through 0,0 towards this vector.
-    #[must_use]
-    pub fn as_rotation(self) -> Rotation {
-        Rotation::radians(self.y.atan2(self.x))
-    }
-
-    /// Returns a vector pointing from `self` to `other`.
-    #[must_use]
-    pub fn vector_to(self, other: Coordinate) -> Vector {
-        Vector::from(other - self)
-    }
-}
-
-impl Add for Coordinate {
-    type Output = Self;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Self {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-        }
-    }
-}
-
-impl Sub for Coordinate {
-    type Output = Self;
+pub use fixed::Fixed;
+pub use hierarchy::{BreadthFirst, DepthFirst, Hierarchy};
+pub use scalar::Scalar;
 
-    fn sub(self, rhs: Self) -> Self::Output {
-        Self {
-            x: self.x - rhs.x,
-            y: self.y - rhs.y,
-        }
-    }
-}
-
-impl Mul<f32> for Coordinate {
-    type Output = Self;
-
-    fn mul(self, rhs: f32) -> Self::Output {
-        Self {
-            x: self.x * rhs,
-            y: self.y * rhs,
-        }
-    }
-}
-
-impl Div<f32> for Coordinate {
-    type Output = Self;
-
-    fn div(self, rhs: f32) -> Self::Output {
-        Self {
-            x: self.x / rhs,
-            y: self.y / rhs,
-        }
-    }
-}
+/// A two dimensionsional offset/measurement.
+pub type Coordinate = generic::Coordinate<f32>;
+/// A value representing a rotation in 2d space.
+pub type Rotation = generic::Rotation<f32>;
+/// A 2D Euclidean vector.
+pub type Vector = generic::Vector<f32>;
+/// A representation of a bone structure inside of a [`Skeleton`].
+pub type BoneKind = generic::BoneKind<f32>;
+/// Settings controlling how a [`BoneKind::Jointed`] bone behaves as its
+/// desired end approaches or exceeds the bone's full length.
+pub type SoftIk = generic::SoftIk<f32>;
+/// A [`BoneKind`] with an associated label.
+pub type LabeledBoneKind = generic::LabeledBoneKind<f32>;
+/// A collection of [`Bone`]s. connected by [`Joint`]s.
+pub type Skeleton = generic::Skeleton<f32>;
+/// A bone in a [`Skeleton`].
+pub type Bone = generic::Bone<f32>;
+/// A connection between two bones.
+pub type Joint = generic::Joint<f32>;
+/// A named location along a [`Bone`] that other objects can be attached to.
+pub type Socket = generic::Socket<f32>;
+/// A [`Skeleton`] attached to another skeleton.
+pub type Attachment = generic::Attachment<f32>;
+/// A copy of the bones, joints, and sockets hanging off of a [`BoneAxis`] in
+/// a [`Skeleton`].
+pub type Subtree = generic::Subtree<f32>;
+/// A report of how well each bone aimed using inverse kinematics reached its
+/// desired end during [`Skeleton::solve_with_report`].
+pub type SolveReport = generic::SolveReport<f32>;
+/// The result of aiming a single [`BoneKind::Jointed`] bone at its desired
+/// end.
+pub type BoneReport = generic::BoneReport<f32>;
 
 /// A value representing a rotation between 0. and `2π`.
 #[derive(Debug, PartialEq, Clone, Copy, PartialOrd, Default)]
@@ -145,902 +95,706 @@ impl From<Rotation> for Angle {
 }
 
 impl Display for Angle {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         Display::fmt(&self.0, f)
     }
 }
 
-/// A value representing a rotation in 2d space.
-#[derive(Clone, Copy, PartialEq, PartialOrd)]
-#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
-pub struct Rotation {
-    radians: f32,
-}
-
-impl Rotation {
-    /// Returns a rotation representing the given radians.
-    #[must_use]
-    pub const fn radians(radians: f32) -> Self {
-        Self { radians }
-    }
-
-    /// Returns a rotation representing the given degrees.
-    #[must_use]
-    pub fn degrees(degrees: f32) -> Self {
-        Self::radians(degrees * PI / 180.0)
-    }
-
-    /// Returns this rotation represented in degrees.
-    #[must_use]
-    pub fn to_degrees(self) -> f32 {
-        self.radians * 180.0 / PI
-    }
-
-    /// Returns this rotation represented in radians.
-    #[must_use]
-    pub const fn to_radians(self) -> f32 {
-        self.radians
-    }
-
-    /// Returns this angle constrained between 0 and `2π`.
-    #[must_use]
-    pub fn clamped(mut self) -> Self {
-        const TWO_PI: f32 = PI * 2.0;
-        while self.radians >= TWO_PI {
-            self.radians -= TWO_PI;
-        }
-        while self.radians < 0. {
-            self.radians += TWO_PI;
-        }
-        self
-    }
-
-    /// Returns the cosine of this angle.
-    #[must_use]
-    pub fn cos(self) -> f32 {
-        self.radians.cos()
-    }
-
-    /// Returns the sine of this angle.
-    #[must_use]
-    pub fn sin(self) -> f32 {
-        self.radians.sin()
-    }
-}
-
-impl Debug for Rotation {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        Display::fmt(self, f)
-    }
-}
-
-impl Display for Rotation {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}°", self.to_degrees())
-    }
-}
-
-impl Default for Rotation {
-    fn default() -> Self {
-        Self { radians: 0. }
-    }
-}
-
-impl Add for Rotation {
-    type Output = Self;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Self::radians(self.radians + rhs.radians)
-    }
-}
-
-impl AddAssign for Rotation {
-    fn add_assign(&mut self, rhs: Self) {
-        self.radians = (*self + rhs).radians;
-    }
-}
-
-impl Sub for Rotation {
-    type Output = Self;
-
-    fn sub(self, rhs: Self) -> Self::Output {
-        Self::radians(self.radians - rhs.radians)
-    }
-}
-
-impl SubAssign for Rotation {
-    fn sub_assign(&mut self, rhs: Self) {
-        self.radians = (*self - rhs).radians;
-    }
-}
-
-impl Neg for Rotation {
-    type Output = Self;
-
-    fn neg(self) -> Self::Output {
-        Self::radians(-self.radians)
-    }
+/// A limit of how far a [`BoneKind::Jointed`] bone can reach.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum ReachLimit {
+    /// The desired end was closer than the difference between the bone's two
+    /// segment lengths.
+    Minimum,
+    /// The desired end was further than the bone's full length.
+    Maximum,
+    /// The desired end was within reach, but [`SoftIk`] eased the bone's end
+    /// short of it.
+    Soft,
 }
 
-/// A 2D Euclidean vector.
-#[derive(Clone, Copy, PartialEq, Debug, Default)]
+/// A specific end of a specific bone.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
 #[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
-pub struct Vector {
-    /// The length of the vector.
-    pub magnitude: f32,
-    /// The direction the vector is heading.
-    pub direction: Rotation,
+pub struct BoneAxis {
+    /// The unique id of the bone of this axis.
+    pub bone: BoneId,
+    /// The end of the bone being referenced.
+    pub end: BoneEnd,
 }
 
-impl Vector {
-    /// Returns a new vector for the given magnitude and direction.
+impl BoneAxis {
+    /// Returns the opposite axis on the same bone.
     #[must_use]
-    pub const fn new(magnitude: f32, direction: Rotation) -> Self {
-        Self {
-            magnitude,
-            direction,
-        }
-    }
-}
-
-impl From<Vector> for Coordinate {
-    fn from(vec: Vector) -> Self {
-        Self {
-            x: vec.magnitude * vec.direction.cos(),
-            y: vec.magnitude * vec.direction.sin(),
-        }
-    }
-}
-
-impl From<Coordinate> for Vector {
-    fn from(pt: Coordinate) -> Self {
+    pub const fn inverse(self) -> Self {
         Self {
-            direction: pt.as_rotation(),
-            magnitude: pt.magnitude(),
+            bone: self.bone,
+            end: self.end.inverse(),
         }
     }
 }
 
-impl Add for Vector {
-    type Output = Self;
-
-    fn add(self, rhs: Vector) -> Self::Output {
-        Vector::from(Coordinate::from(self) + Coordinate::from(rhs))
-    }
-}
-
-impl Add<Vector> for Coordinate {
-    type Output = Self;
-
-    fn add(self, rhs: Vector) -> Self::Output {
-        self + Coordinate::from(rhs)
-    }
-}
-
-impl Sub<Vector> for Coordinate {
-    type Output = Self;
-
-    fn sub(self, rhs: Vector) -> Self::Output {
-        self - Coordinate::from(rhs)
-    }
-}
-
-impl Add<Rotation> for Vector {
-    type Output = Self;
-
-    fn add(mut self, rhs: Rotation) -> Self::Output {
-        self.direction += rhs;
-        self
-    }
-}
-
-impl Sub<Rotation> for Vector {
-    type Output = Self;
-
-    fn sub(mut self, rhs: Rotation) -> Self::Output {
-        self.direction -= rhs;
-        self
-    }
-}
-
-/// A representation of a bone structure inside of a [`Skeleton`].
-#[derive(Debug, Clone, Copy, PartialEq)]
+/// The unique ID of a [`Bone`] in a [`Skeleton`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
 #[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
-pub enum BoneKind {
-    /// A single bone of a fixed length.
-    Rigid {
-        /// The length of the bone.
-        length: f32,
-    },
-    /// Two bones connected with a joint that automatically adjusts its angle as
-    /// needed.
-    Jointed {
-        /// The length of the bone connected closest to the root of the
-        /// skeleton.
-        start_length: f32,
-        /// The length of the bone connected furthes from the root of the
-        /// skeleton.
-        end_length: f32,
-        /// The bend of the simulated joint always goes in one of two
-        /// directions. This boolean toggles which direction the bend goes in.
-        inverse: bool,
-    },
-}
+pub struct BoneId(u16);
 
-impl BoneKind {
-    /// Attaches a label to this bone when pushed into a skeleton.
+impl BoneId {
+    /// Returns the first axis of this bone.
     #[must_use]
-    pub fn with_label(self, label: impl Into<String>) -> LabeledBoneKind {
-        LabeledBoneKind {
-            kind: self,
-            label: label.into(),
+    pub const fn axis_a(self) -> BoneAxis {
+        BoneAxis {
+            bone: self,
+            end: BoneEnd::A,
         }
     }
 
-    /// Returns the full length of this bone.
+    /// Returns the second axis of this bone.
     #[must_use]
-    pub fn full_length(&self) -> f32 {
-        match self {
-            BoneKind::Rigid { length } => *length,
-            BoneKind::Jointed {
-                start_length,
-                end_length,
-                ..
-            } => *start_length + *end_length,
+    pub const fn axis_b(self) -> BoneAxis {
+        BoneAxis {
+            bone: self,
+            end: BoneEnd::B,
         }
     }
 
-    /// Returns true if this is a jointed bone that bends inversely.
+    /// Returns the index of this bone within the skeleton.
     #[must_use]
-    pub fn is_inverse(&self) -> bool {
-        match self {
-            BoneKind::Rigid { .. } => false,
-            BoneKind::Jointed { inverse, .. } => *inverse,
-        }
-    }
-
-    /// Sets whether to bend inversely.
-    ///
-    /// This function only affects a jointed bone kind.
-    pub fn set_inverse(&mut self, new_inverse: bool) {
-        let BoneKind::Jointed { inverse, .. } = self else {
-            return;
-        };
-        *inverse = new_inverse;
+    pub fn index(self) -> usize {
+        usize::from(self.0)
     }
 }
 
-/// A [`BoneKind`] with an associated label.
-pub struct LabeledBoneKind {
-    /// The bone to create.
-    pub kind: BoneKind,
-    /// The label of the bone.
-    pub label: String,
-}
+/// The unique ID of a [`Joint`] in a [`Skeleton`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub struct JointId(u16);
 
-impl From<BoneKind> for LabeledBoneKind {
-    fn from(kind: BoneKind) -> Self {
-        kind.with_label(String::new())
+impl JointId {
+    /// Returns the index of this joint within the skeleton.
+    #[must_use]
+    pub fn index(self) -> usize {
+        usize::from(self.0)
     }
 }
 
-#[derive(Clone, Eq, PartialEq, Hash, Debug)]
-struct ArcString(Arc<String>);
+/// The unique ID of a [`Socket`] in a [`Skeleton`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub struct SocketId(u16);
 
-impl PartialEq<str> for ArcString {
-    fn eq(&self, other: &str) -> bool {
-        &**self == other
+impl SocketId {
+    /// Returns the index of this socket within the skeleton.
+    #[must_use]
+    pub fn index(self) -> usize {
+        usize::from(self.0)
     }
 }
 
-impl Deref for ArcString {
-    type Target = str;
+/// The unique ID of an [`Attachment`] in a [`Skeleton`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub struct AttachmentId(u16);
 
-    fn deref(&self) -> &Self::Target {
-        &self.0
+impl AttachmentId {
+    /// Returns the index of this attachment within the skeleton.
+    #[must_use]
+    pub fn index(self) -> usize {
+        usize::from(self.0)
     }
 }
 
-impl Borrow<str> for ArcString {
-    fn borrow(&self) -> &str {
-        self
-    }
+/// A mapping of ids copied from one part of a [`Skeleton`] to the ids of their
+/// copies.
+///
+/// This map can be used to retarget animations to the copies using
+/// [`Animation::remapped`](animation::Animation::remapped).
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct IdMap {
+    bones: BTreeMap<BoneId, BoneId>,
+    joints: BTreeMap<JointId, JointId>,
+    sockets: BTreeMap<SocketId, SocketId>,
 }
 
-/// A collection of [`Bone`]s. connected by [`Joint`]s.
-#[derive(Default, Debug, PartialEq)]
-pub struct Skeleton {
-    bones: Vec<Bone>,
-    rotation: Rotation,
-    joints: Vec<Joint>,
-    connections: HashMap<BoneAxis, Vec<JointId>>,
-    generation: usize,
-    bones_by_label: HashMap<ArcString, BoneId>,
-    joints_by_label: HashMap<ArcString, JointId>,
-}
-
-impl Skeleton {
-    /// Creates a new [`Bone`] into the skeleton. Returns the unique id of the
-    /// created bone.
-    ///
-    /// The first bone pushed is considered the root of the skeleton. All other
-    /// bones must be connected to the root directly or indirectly through
-    /// [`Joint`]s.
-    pub fn push_bone(&mut self, bone: impl Into<LabeledBoneKind>) -> BoneId {
-        let bone = bone.into();
-        let id = BoneId(u16::try_from(self.bones.len()).expect("too many bones"));
-
-        let label = if bone.label.is_empty() {
-            None
-        } else {
-            let label = ArcString(Arc::new(bone.label));
-            self.bones_by_label.insert(label.clone(), id);
-            Some(label)
-        };
-        self.bones.push(Bone {
-            id,
-            generation: self.generation,
-            label,
-            kind: bone.kind,
-            start: Coordinate::default(),
-            joint_pos: None,
-            end: Coordinate::default(),
-            desired_end: None,
-            entry_angle: Rotation::default(),
-        });
-        id
-    }
-
-    /// Returns the list of bones in this skeleton.
-    #[must_use]
-    pub fn bones(&self) -> &[Bone] {
-        &self.bones
-    }
-
-    /// Returns the list of joints in this skeleton.
-    #[must_use]
-    pub fn joints(&self) -> &[Joint] {
-        &self.joints
-    }
-
-    /// Returns the bone for a given id, or none if not found.
-    #[must_use]
-    pub fn bone(&self, id: BoneId) -> Option<&Bone> {
-        self.bones.get(id.index())
-    }
-
-    /// Returns an exclusive reference to the bone for a given id, or none if
-    /// not found.
-    #[must_use]
-    pub fn bone_mut(&mut self, id: BoneId) -> Option<&mut Bone> {
-        self.bones.get_mut(id.index())
-    }
-
-    /// Returns the joint for a given id, or none if not found.
-    #[must_use]
-    pub fn joint(&self, id: JointId) -> Option<&Joint> {
-        self.joints.get(id.index())
-    }
-
-    /// Returns an exclusive reference to the joint for a given id, or none if
-    /// not found.
-    #[must_use]
-    pub fn joint_mut(&mut self, id: JointId) -> Option<&mut Joint> {
-        self.joints.get_mut(id.index())
-    }
-
-    /// Returns a list of joints connected to a specific bone axis.
+impl IdMap {
+    /// Returns the id of the copy of `original`, if it was copied.
     #[must_use]
-    pub fn connections_to(&self, axis: BoneAxis) -> Option<&[JointId]> {
-        self.connections.get(&axis).map(Vec::as_slice)
+    pub fn bone(&self, original: BoneId) -> Option<BoneId> {
+        self.bones.get(&original).copied()
     }
 
-    /// Creates a new [`Joint`] in the skeleton, connecting two bones together
-    /// by their [axis](BoneAxis). Returns the unique id of the created joint.
-    pub fn push_joint(&mut self, mut joint: Joint) -> JointId {
-        let id = JointId(u16::try_from(self.joints.len()).expect("too many joints"));
-        joint.id = id;
-        let bone_a = joint.bone_a;
-        let bone_b = joint.bone_b;
-        if let Some(label) = joint.label.clone() {
-            self.joints_by_label.insert(label, id);
-        }
-        self.joints.push(joint);
-        self.connections.entry(bone_a).or_default().push(id);
-        if bone_a != bone_b {
-            self.connections.entry(bone_b).or_default().push(id);
-        }
-        id
-    }
-
-    /// Finds an existing [`Joint`] by its label.
+    /// Returns the id of the copy of `original`, if it was copied.
     #[must_use]
-    pub fn find_joint_by_label(&self, label: &str) -> Option<JointId> {
-        self.joints_by_label.get(label).copied()
+    pub fn joint(&self, original: JointId) -> Option<JointId> {
+        self.joints.get(&original).copied()
     }
 
-    /// Finds an existing [`Bone`] by its label.
+    /// Returns the id of the copy of `original`, if it was copied.
     #[must_use]
-    pub fn find_bone_by_label(&self, label: &str) -> Option<BoneId> {
-        self.bones_by_label.get(label).copied()
+    pub fn socket(&self, original: SocketId) -> Option<SocketId> {
+        self.sockets.get(&original).copied()
     }
 
-    /// Sets a translation to be applied to the entire skeleton.
-    pub fn set_translation(&mut self, translation: Coordinate) {
-        let bone = self.bones.first_mut().expect("root bone must be defined");
-        bone.start = translation;
+    /// Returns an iterator over each copied bone's original and new ids.
+    pub fn bones(&self) -> impl Iterator<Item = (BoneId, BoneId)> + '_ {
+        self.bones.iter().map(|(from, to)| (*from, *to))
     }
 
-    /// Returns the translation applied to the entire skeleton.
-    #[must_use]
-    pub fn translation(&self) -> Coordinate {
-        self.bones.first().expect("root bone must be defined").start
+    /// Returns an iterator over each copied joint's original and new ids.
+    pub fn joints(&self) -> impl Iterator<Item = (JointId, JointId)> + '_ {
+        self.joints.iter().map(|(from, to)| (*from, *to))
     }
 
-    /// Sets a base rotation to apply to the entire skeleton.
-    pub fn set_rotation(&mut self, rotation: Rotation) {
-        self.rotation = rotation;
-    }
-
-    /// Returns the base rotation being applied to the entire skeleton.
-    #[must_use]
-    pub const fn rotation(&self) -> Rotation {
-        self.rotation
-    }
-
-    /// Updates the solved positions of all bones in this skeleton that are
-    /// connected either directly or indirectly to the root bone via [`Joint`]s.
-    pub fn solve(&mut self) {
-        if !self.bones.is_empty() {
-            self.generation = self.generation.wrapping_add(1);
-            self.solve_axis();
-        }
-    }
-
-    fn solve_axis(&mut self) {
-        let mut axis_solved = HashSet::new();
-        let root_bone = &mut self.bones[0];
-        let (end, mid, _) = determine_end_position(
-            root_bone.start,
-            root_bone.desired_end,
-            self.rotation,
-            Rotation::radians(0.),
-            &root_bone.kind,
-        );
-        root_bone.entry_angle = self.rotation;
-        root_bone.end = end;
-        root_bone.joint_pos = mid;
-        let angle = root_bone.final_vector().direction;
-
-        let mut to_solve = vec![
-            (
-                root_bone.id.axis_a(),
-                root_bone.start,
-                angle + Rotation::radians(PI),
-            ),
-            (root_bone.id.axis_b(), root_bone.end, angle),
-        ];
-        while let Some((axis, current_position, current_rotation)) = to_solve.pop() {
-            if !axis_solved.insert(axis) {
-                continue;
-            }
-
-            let Some(connections) = self.connections.get(&axis) else {
-                continue;
-            };
-
-            for joint_id in connections {
-                let joint = &mut self.joints[joint_id.index()];
-                let other_axis = joint.other_axis(axis);
-                let bone = &mut self.bones[other_axis.bone.index()];
-                if bone.generation == self.generation {
-                    // We store connections in both directions, which means we
-                    // can visit bones twice. We want to ensure we only follow
-                    // each bone a single time.
-                    continue;
-                }
-                bone.generation = self.generation;
-                bone.entry_angle = current_rotation;
-                bone.start = current_position;
-                joint.calculated_position = current_position;
-
-                let (end, mid, angle_offset) = determine_end_position(
-                    current_position,
-                    bone.desired_end,
-                    current_rotation,
-                    joint.angle,
-                    &bone.kind,
-                );
-                bone.entry_angle += angle_offset;
-                bone.end = end;
-                bone.joint_pos = mid;
-
-                to_solve.push((
-                    other_axis.inverse(),
-                    bone.end,
-                    bone.final_vector().direction,
-                ));
-            }
-        }
+    /// Returns an iterator over each copied socket's original and new ids.
+    pub fn sockets(&self) -> impl Iterator<Item = (SocketId, SocketId)> + '_ {
+        self.sockets.iter().map(|(from, to)| (*from, *to))
     }
 }
 
-fn determine_end_position(
-    start: Coordinate,
-    desired_end: Option<Vector>,
-    current_rotation: Rotation,
-    joint_angle: Rotation,
-    bone: &BoneKind,
-) -> (Coordinate, Option<Coordinate>, Rotation) {
-    let entry_angle = current_rotation + joint_angle;
-    match bone {
-        BoneKind::Rigid { length } => (
-            start + Vector::new(*length, entry_angle),
-            None,
-            Rotation::default(),
-        ),
-        BoneKind::Jointed {
-            start_length,
-            end_length,
-            inverse,
-        } => {
-            if let Some(desired_end) = desired_end {
-                let desired_angle = desired_end.direction + entry_angle;
-                let distance = desired_end.magnitude;
-                let full_length = start_length + end_length;
-                let minimum_size = (start_length - end_length).abs();
-                let desired_length = if distance < minimum_size {
-                    minimum_size
-                } else if distance > full_length {
-                    full_length
-                } else {
-                    distance
-                };
-
-                let end = start + Vector::new(desired_length, desired_angle);
-
-                let joint = get_third_point(
-                    *inverse,
-                    start,
-                    desired_length,
-                    desired_angle,
-                    *start_length,
-                    *end_length,
-                );
-
-                (end, Some(joint), joint_angle)
-            } else {
-                let joint = start + Vector::new(*start_length, entry_angle);
-                let end = joint + Vector::new(*end_length, entry_angle);
-                (end, Some(joint), joint_angle)
-            }
-        }
-    }
-}
-
-fn get_third_point(
-    inverse: bool,
-    start: Coordinate,
-    distance: f32,
-    hyp_angle: Rotation,
-    first: f32,
-    second: f32,
-) -> Coordinate {
-    let hyp = distance;
-    let first_angle = ((first * first + hyp * hyp - second * second) / (2. * first * hyp)).acos();
-    if first_angle.is_nan() {
-        start + Vector::new(first, hyp_angle)
-    } else {
-        let first_angle = hyp_angle
-            - Rotation {
-                radians: if inverse { -first_angle } else { first_angle },
-            };
-        start + Vector::new(first, first_angle)
-    }
-}
-
-impl Index<BoneId> for Skeleton {
-    type Output = Bone;
-
-    fn index(&self, index: BoneId) -> &Self::Output {
-        &self.bones[index.index()]
-    }
-}
-
-impl IndexMut<BoneId> for Skeleton {
-    fn index_mut(&mut self, index: BoneId) -> &mut Self::Output {
-        &mut self.bones[index.index()]
-    }
-}
-
-impl Index<JointId> for Skeleton {
-    type Output = Joint;
-
-    fn index(&self, index: JointId) -> &Self::Output {
-        &self.joints[index.index()]
-    }
+/// A location in a [`Skeleton`] that another skeleton can be attached to.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub enum AttachmentPoint {
+    /// The end of a bone. The attachment's direction points away from the
+    /// bone.
+    Axis(BoneAxis),
+    /// A socket. The attachment's direction is the socket's rotation.
+    Socket(SocketId),
 }
 
-impl IndexMut<JointId> for Skeleton {
-    fn index_mut(&mut self, index: JointId) -> &mut Self::Output {
-        &mut self.joints[index.index()]
-    }
+/// A segment of a [`Bone`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub enum BoneSegment {
+    /// The segment between the start of the bone and its joint.
+    #[default]
+    Start,
+    /// The segment between the joint of the bone and its end.
+    End,
 }
 
-/// A specific end of a specific bone.
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+/// A specific end of a [`Bone`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
 #[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
-pub struct BoneAxis {
-    /// The unique id of the bone of this axis.
-    pub bone: BoneId,
-    /// The end of the bone being referenced.
-    pub end: BoneEnd,
+pub enum BoneEnd {
+    /// The first end of a bone.
+    A,
+    /// The second end of a bone.
+    B,
 }
 
-impl BoneAxis {
-    /// Returns the opposite axis on the same bone.
+impl BoneEnd {
+    /// Returns the opposite end of `self`.
     #[must_use]
     pub const fn inverse(self) -> Self {
-        Self {
-            bone: self.bone,
-            end: self.end.inverse(),
+        match self {
+            Self::A => Self::B,
+            Self::B => Self::A,
         }
     }
 }
 
-/// A bone in a [`Skeleton`].
-#[derive(Debug, PartialEq)]
-pub struct Bone {
-    id: BoneId,
-    generation: usize,
-    label: Option<ArcString>,
-    kind: BoneKind,
-    start: Coordinate,
-    joint_pos: Option<Coordinate>,
-    end: Coordinate,
-    desired_end: Option<Vector>,
-    entry_angle: Rotation,
+/// The direction the positive y axis points in a [`CoordinateSystem`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub enum YAxis {
+    /// Positive y values are below the origin, as is common in screen space.
+    #[default]
+    Down,
+    /// Positive y values are above the origin, as is common in physics engines.
+    Up,
 }
 
-impl Bone {
-    /// Returns the unique id of this bone.
-    #[must_use]
-    pub const fn id(&self) -> BoneId {
-        self.id
-    }
-
-    /// Returns true if this bone is the root of the skeleton.
-    #[must_use]
-    pub const fn is_root(&self) -> bool {
-        self.id.0 == 0
-    }
+/// The direction positive [`Rotation`]s turn in a [`CoordinateSystem`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub enum Winding {
+    /// Positive rotations turn clockwise.
+    #[default]
+    Clockwise,
+    /// Positive rotations turn counter-clockwise.
+    CounterClockwise,
+}
+
+/// The conventions used to interpret a [`Skeleton`]'s world-space positions
+/// and rotations.
+///
+/// The default coordinate system matches screen space: the positive y axis
+/// points down and positive rotations turn clockwise.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub struct CoordinateSystem {
+    /// The direction the positive y axis points.
+    pub y_axis: YAxis,
+    /// The direction positive rotations turn.
+    pub winding: Winding,
+}
 
-    /// Returns the kind of this bone.
-    #[must_use]
-    pub const fn kind(&self) -> &BoneKind {
-        &self.kind
-    }
+impl CoordinateSystem {
+    /// A coordinate system where the positive y axis points down and positive
+    /// rotations turn clockwise.
+    pub const Y_DOWN: Self = Self::new(YAxis::Down, Winding::Clockwise);
+    /// A coordinate system where the positive y axis points up and positive
+    /// rotations turn counter-clockwise.
+    pub const Y_UP: Self = Self::new(YAxis::Up, Winding::CounterClockwise);
 
-    /// Returns an exclusive reference to the kind of this bone.
+    /// Returns a coordinate system using the given conventions.
     #[must_use]
-    pub fn kind_mut(&mut self) -> &mut BoneKind {
-        &mut self.kind
+    pub const fn new(y_axis: YAxis, winding: Winding) -> Self {
+        Self { y_axis, winding }
     }
 
-    /// Sets a relative position to aim the end of this bone towards.
-    ///
-    /// The end of the bone that is aimed is the end that is furthest from the
-    /// root of the skeleton.
-    ///
-    /// This setting only impacts [`BoneKind::Jointed`] bones.
-    pub fn set_desired_end(&mut self, end: Option<Vector>) {
-        self.desired_end = end;
-    }
-
-    /// Returns the location this bone is being aimed towards.
+    /// Returns true if positive rotations turn from the positive x axis away
+    /// from the positive y axis.
     #[must_use]
-    pub const fn desired_end(&self) -> Option<Vector> {
-        self.desired_end
+    pub const fn is_mirrored(self) -> bool {
+        matches!(
+            (self.y_axis, self.winding),
+            (YAxis::Down, Winding::CounterClockwise) | (YAxis::Up, Winding::Clockwise)
+        )
     }
 
-    /// Returns the angle of the previous bone segment connecting to this bone.
+    /// Returns the world-space offset described by `vector`.
     #[must_use]
-    pub const fn entry_angle(&self) -> Rotation {
-        self.entry_angle
+    pub fn to_coordinate<T: Scalar>(self, vector: generic::Vector<T>) -> generic::Coordinate<T> {
+        self.mirror(generic::Coordinate::from(vector))
     }
 
-    /// Returns the solved start position of this bone.
+    /// Returns the vector describing the world-space offset `coordinate`.
     #[must_use]
-    pub const fn start(&self) -> Coordinate {
-        self.start
+    pub fn to_vector<T: Scalar>(self, coordinate: generic::Coordinate<T>) -> generic::Vector<T> {
+        generic::Vector::from(self.mirror(coordinate))
     }
 
-    /// Returns the solved end position of this bone.
-    #[must_use]
-    pub const fn end(&self) -> Coordinate {
-        self.end
+    /// Converts between world space and the space bones are solved in.
+    pub(crate) fn mirror<T: Scalar>(
+        self,
+        coordinate: generic::Coordinate<T>,
+    ) -> generic::Coordinate<T> {
+        if self.is_mirrored() {
+            generic::Coordinate::new(coordinate.x, -coordinate.y)
+        } else {
+            coordinate
+        }
     }
+}
 
-    /// Returns a vector representing the final segment in this bone.
-    #[must_use]
-    pub fn final_vector(&self) -> Vector {
-        let start = self.joint_pos.unwrap_or(self.start);
-        start.vector_to(self.end)
-    }
+#[test]
+#[allow(clippy::cast_possible_truncation)]
+fn rotation() {
+    assert_eq!(
+        (Rotation::degrees(90.) + Rotation::degrees(180.))
+            .clamped()
+            .to_degrees()
+            .round() as i32,
+        270,
+    );
+    assert_eq!(
+        (Rotation::degrees(90.) + Rotation::degrees(-180.))
+            .clamped()
+            .to_degrees()
+            .round() as i32,
+        270,
+    );
+}
 
-    /// If this is a [`BoneKind::Jointed`] bone, returns the solved position of
-    /// the joint.
-    #[must_use]
-    pub const fn solved_joint(&self) -> Option<Coordinate> {
-        self.joint_pos
-    }
+#[test]
+fn coordinate_system() {
+    let mut skeleton = Skeleton::default();
+    let root = skeleton.push_bone(BoneKind::Rigid { length: 1. });
+    let arm = skeleton.push_bone(BoneKind::Rigid { length: 1. });
+    skeleton.push_joint(Joint::new(
+        Rotation::degrees(90.),
+        root.axis_b(),
+        arm.axis_a(),
+    ));
+    skeleton.set_translation(Coordinate::new(0., 1.));
+
+    let solve = |skeleton: &mut Skeleton, system: CoordinateSystem| {
+        skeleton.set_coordinate_system(system);
+        skeleton.solve();
+        let end = skeleton[arm].end();
+        ((end.x * 1000.).round(), (end.y * 1000.).round())
+    };
+    // In screen space, turning 90 degrees clockwise from the x axis heads
+    // down, which is towards positive y.
+    assert_eq!(
+        solve(&mut skeleton, CoordinateSystem::Y_DOWN),
+        (1000., 2000.)
+    );
+    // Turning counter-clockwise in a y-up space heads up, which is also
+    // towards positive y.
+    assert_eq!(solve(&mut skeleton, CoordinateSystem::Y_UP), (1000., 2000.));
+    // Turning clockwise in a y-up space heads down, towards negative y.
+    let system = CoordinateSystem::new(YAxis::Up, Winding::Clockwise);
+    assert_eq!(solve(&mut skeleton, system), (1000., 0.));
+    assert_eq!(skeleton.translation(), Coordinate::new(0., 1.));
+    let direction = skeleton[arm].final_vector().direction.to_degrees().round();
+    assert!((direction - 90.).abs() < f32::EPSILON);
+    assert_eq!(
+        system.to_coordinate(Vector::new(1., Rotation::degrees(90.))),
+        Coordinate::new(Rotation::degrees(90.).cos(), -1.)
+    );
+}
 
-    /// Returns the label this bone was created with.
-    #[must_use]
-    pub fn label(&self) -> &str {
-        self.label.as_ref().map_or("", |s| s)
-    }
+#[test]
+fn scale_and_flip() {
+    let mut skeleton = Skeleton::default();
+    let root = skeleton.push_bone(BoneKind::Rigid { length: 1. });
+    let leg = skeleton.push_bone(BoneKind::Jointed {
+        start_length: 1.,
+        end_length: 1.,
+        inverse: false,
+    });
+    skeleton.push_joint(Joint::new(Rotation::default(), root.axis_b(), leg.axis_a()));
+    skeleton[leg].set_desired_end(Some(Vector::new(1.5, Rotation::degrees(90.))));
+    skeleton.set_translation(Coordinate::new(1., 1.));
+    skeleton.solve();
+    let end = skeleton[leg].end();
+    let joint = skeleton[leg].solved_joint().expect("jointed");
+
+    skeleton.set_scale(2.);
+    skeleton.set_flip_horizontal(true);
+    skeleton.solve();
+    let flipped = skeleton[leg].end();
+    assert!((flipped.x - (1. - (end.x - 1.) * 2.)).abs() < 0.0001);
+    assert!((flipped.y - (1. + (end.y - 1.) * 2.)).abs() < 0.0001);
+    // The joint bends the same way relative to the mirrored pose.
+    let flipped_joint = skeleton[leg].solved_joint().expect("jointed");
+    assert!((flipped_joint.x - (1. - (joint.x - 1.) * 2.)).abs() < 0.0001);
+
+    // Converting the solved end back into local space recovers the target.
+    let target =
+        skeleton.to_local_vector(flipped - skeleton[leg].start()) - skeleton[leg].entry_angle();
+    assert!((target.magnitude - 1.5).abs() < 0.0001);
+    assert!((target.direction.to_degrees() - 90.).abs() < 0.01);
+
+    skeleton.set_flip_horizontal(false);
+    skeleton.set_flip_vertical(true);
+    skeleton.solve();
+    let flipped = skeleton[leg].end();
+    assert!((flipped.x - (1. + (end.x - 1.) * 2.)).abs() < 0.0001);
+    assert!((flipped.y - (1. - (end.y - 1.) * 2.)).abs() < 0.0001);
 }
 
-/// A connection between two bones.
-#[derive(Debug, PartialEq)]
-pub struct Joint {
-    id: JointId,
-    label: Option<ArcString>,
-    bone_a: BoneAxis,
-    bone_b: BoneAxis,
-    calculated_position: Coordinate,
-    angle: Rotation,
+#[test]
+fn sockets() {
+    let mut skeleton = Skeleton::default();
+    let root = skeleton.push_bone(BoneKind::Rigid { length: 1. });
+    let arm = skeleton.push_bone(BoneKind::Jointed {
+        start_length: 1.,
+        end_length: 1.,
+        inverse: false,
+    });
+    skeleton.push_joint(Joint::new(
+        Rotation::degrees(90.),
+        root.axis_b(),
+        arm.axis_a(),
+    ));
+    let hand = skeleton.push_socket(
+        Socket::new(arm, BoneSegment::End, 0.8)
+            .with_offset(Vector::new(0.1, Rotation::degrees(90.)))
+            .with_label("hand"),
+    );
+    let shoulder = skeleton.push_socket(Socket::new(root, BoneSegment::End, 0.5));
+    skeleton.solve();
+
+    assert_eq!(skeleton.find_socket_by_label("hand"), Some(hand));
+    // The arm extends straight down from the end of the root bone, which
+    // places the hand socket 80% along the forearm, offset to the left.
+    let position = skeleton[hand].position();
+    assert!((position.x - 0.9).abs() < 0.0001);
+    assert!((position.y - 1.8).abs() < 0.0001);
+    assert!((skeleton[hand].rotation().to_degrees() - 90.).abs() < 0.01);
+    let position = skeleton[shoulder].position();
+    assert!((position.x - 0.5).abs() < 0.0001);
+    assert!(position.y.abs() < 0.0001);
+
+    skeleton.set_flip_horizontal(true);
+    skeleton.solve();
+    let position = skeleton[hand].position();
+    assert!((position.x + 0.9).abs() < 0.0001);
+    assert!((position.y - 1.8).abs() < 0.0001);
+    assert!((skeleton[hand].rotation().to_degrees() - 90.).abs() < 0.01);
 }
 
-impl Joint {
-    /// Returns the unique id of this joint.
-    #[must_use]
-    pub const fn id(&self) -> JointId {
-        self.id
-    }
+#[test]
+fn attachments() {
+    let mut horse = Skeleton::default();
+    let back = horse.push_bone(BoneKind::Rigid { length: 2. });
+    let saddle = horse.push_socket(Socket::new(back, BoneSegment::Start, 0.5));
+    horse.set_translation(Coordinate::new(1., 1.));
+
+    let mut rider = Skeleton::default();
+    let spine = rider.push_bone(BoneKind::Rigid { length: 1. });
+    rider.set_rotation(Rotation::degrees(-90.));
+    let attachment = horse.attach(rider, AttachmentPoint::Socket(saddle));
+    horse.solve();
+
+    let rider = horse.attachment(attachment).expect("attached").skeleton();
+    assert_eq!(rider[spine].start(), Coordinate::new(2., 1.));
+    assert!((rider[spine].end().x - 2.).abs() < 0.0001);
+    assert!((rider[spine].end().y - 0.).abs() < 0.0001);
+
+    horse.set_rotation(Rotation::degrees(90.));
+    horse.solve();
+    let rider = horse.attachment(attachment).expect("attached").skeleton();
+    assert!((rider[spine].start().x - 1.).abs() < 0.0001);
+    assert!((rider[spine].start().y - 2.).abs() < 0.0001);
+    assert!((rider[spine].end().x - 2.).abs() < 0.0001);
+    assert!((rider[spine].end().y - 2.).abs() < 0.0001);
+
+    let tail = horse.attach(Skeleton::default(), AttachmentPoint::Axis(back.axis_a()));
+    horse.solve();
+
+    let mut rider = horse.detach(attachment).expect("attached");
+    assert!(horse.attachment(attachment).is_none());
+    assert!(horse.attachment(tail).is_some());
+    rider.solve();
+    assert!((rider[spine].end().x - 2.).abs() < 0.0001);
+    assert!((rider[spine].end().y - 2.).abs() < 0.0001);
+}
 
-    /// Returns a new joint formed by joining `bone_a` and `bone_b` at `angle`.
-    #[must_use]
-    pub const fn new(angle: Rotation, bone_a: BoneAxis, bone_b: BoneAxis) -> Self {
-        Self {
-            id: JointId(0),
-            label: None,
-            bone_a,
-            bone_b,
-            calculated_position: Coordinate::new(0., 0.),
-            angle,
+#[test]
+fn duplicate_subtree() {
+    use alloc::format;
+
+    let mut skeleton = Skeleton::default();
+    let spine = skeleton.push_bone(BoneKind::Rigid { length: 2. }.with_label("spine"));
+    let shoulder = skeleton.push_bone(BoneKind::Rigid { length: 0.5 }.with_label("shoulder"));
+    skeleton.push_joint(Joint::new(
+        Rotation::degrees(90.),
+        spine.axis_b(),
+        shoulder.axis_a(),
+    ));
+    let arm = skeleton.push_bone(
+        BoneKind::Jointed {
+            start_length: 1.,
+            end_length: 1.,
+            inverse: false,
         }
-    }
+        .with_label("arm"),
+    );
+    let elbow = skeleton.push_joint(
+        Joint::new(Rotation::degrees(0.), shoulder.axis_b(), arm.axis_a()).with_label("elbow"),
+    );
+    skeleton[arm].set_desired_end(Some(Vector::new(1.5, Rotation::degrees(45.))));
+    let hand = skeleton.push_socket(Socket::new(arm, BoneSegment::End, 1.).with_label("hand"));
 
-    /// Labels this joint and returns self.
-    #[must_use]
-    pub fn with_label(mut self, label: impl Into<String>) -> Self {
-        let label = label.into();
-        if !label.is_empty() {
-            self.label = Some(ArcString(Arc::new(label)));
-        }
-        self
-    }
+    let ids = skeleton.duplicate_subtree(spine.axis_b(), spine.axis_a(), |label| {
+        format!("lower_{label}")
+    });
+    assert_eq!(skeleton.bones().len(), 5);
+    assert_eq!(skeleton.joints().len(), 4);
+    let new_arm = ids.bone(arm).expect("copied");
+    assert_eq!(skeleton.find_bone_by_label("lower_arm"), Some(new_arm));
+    assert_eq!(skeleton.find_bone_by_label("arm"), Some(arm));
+    assert_eq!(
+        skeleton.find_joint_by_label("lower_elbow"),
+        ids.joint(elbow)
+    );
+    assert_eq!(skeleton[new_arm].desired_end(), skeleton[arm].desired_end());
+    assert!(ids.bone(spine).is_none());
+    let new_hand = ids.socket(hand).expect("copied");
+    assert_eq!(skeleton[new_hand].bone(), new_arm);
+
+    let mut other = Skeleton::default();
+    let root = other.push_bone(BoneKind::Rigid { length: 1. });
+    let subtree = skeleton.clone_subtree(shoulder.axis_b());
+    assert_eq!(subtree.bones().len(), 1);
+    let ids = other.insert_subtree(&subtree, root.axis_b(), |label| label.into());
+    let copied_arm = ids.bone(arm).expect("copied");
+    other.solve();
+    assert_eq!(other[copied_arm].label(), "arm");
+    assert!((other[copied_arm].start().x - 1.).abs() < 0.0001);
+}
 
-    /// Returns the label of this joint.
-    #[must_use]
-    pub fn label(&self) -> &str {
-        self.label.as_ref().map_or("", |s| s)
-    }
+#[test]
+fn hierarchy() {
+    use alloc::{vec, vec::Vec};
+
+    let mut skeleton = Skeleton::default();
+    let spine = skeleton.push_bone(BoneKind::Rigid { length: 2. });
+    let hip = skeleton.push_bone(BoneKind::Rigid { length: 0.5 });
+    let hip_joint = skeleton.push_joint(Joint::new(
+        Rotation::degrees(90.),
+        spine.axis_a(),
+        hip.axis_a(),
+    ));
+    let leg = skeleton.push_bone(BoneKind::Rigid { length: 1. });
+    let knee = skeleton.push_joint(Joint::new(Rotation::default(), hip.axis_b(), leg.axis_a()));
+    let head = skeleton.push_bone(BoneKind::Rigid { length: 0.5 });
+    let neck = skeleton.push_joint(Joint::new(
+        Rotation::default(),
+        spine.axis_b(),
+        head.axis_a(),
+    ));
+    let orphan = skeleton.push_bone(BoneKind::Rigid { length: 1. });
+
+    let hierarchy = skeleton.hierarchy();
+    assert_eq!(hierarchy.root(), Some(spine));
+    assert_eq!(hierarchy.parent_joint(spine), None);
+    assert_eq!(hierarchy.parent_joint(leg), Some(knee));
+    assert_eq!(hierarchy.parent(leg), Some(hip.axis_b()));
+    assert_eq!(
+        hierarchy.children(spine).collect::<Vec<_>>(),
+        vec![(neck, head), (hip_joint, hip)]
+    );
+    assert_eq!(
+        hierarchy.depth_first(spine).collect::<Vec<_>>(),
+        vec![spine, head, hip, leg]
+    );
+    assert_eq!(
+        hierarchy.breadth_first(spine).collect::<Vec<_>>(),
+        vec![spine, head, hip, leg]
+    );
+    assert_eq!(hierarchy.path_to(leg), Some(vec![spine, hip, leg]));
+    assert_eq!(hierarchy.path_to(orphan), None);
+    assert!(!hierarchy.contains(orphan));
+    assert_eq!(
+        hierarchy
+            .downstream_of(hip_joint)
+            .into_iter()
+            .collect::<Vec<_>>(),
+        vec![hip, leg]
+    );
+}
 
-    /// Given `axis` is one of the two connections in this joint, return the
-    /// other axis.
-    ///
-    /// # Panics
-    ///
-    /// This function has a debug assertion that ensures that `axis` is one of
-    /// the bones in this joint.
-    #[must_use]
-    pub fn other_axis(&self, axis: BoneAxis) -> BoneAxis {
-        if self.bone_a == axis {
-            self.bone_b
-        } else {
-            debug_assert_eq!(self.bone_b, axis);
-            self.bone_a
+#[test]
+fn skeleton_macro() {
+    skeleton! {
+        struct Body {
+            spine: Rigid { length: 2. },
+            r_hip: Rigid { length: 0.5 } from spine.a at 90.,
+            r_leg: Jointed { start_length: 1., end_length: 1., inverse: true }
+                from r_hip.b as r_knee at -90.,
+            head: Rigid { length: 0.5 } from spine.b as neck at 0.,
         }
     }
 
-    /// Sets the angle to form between these joints.
-    ///
-    /// This setting is ignored if the bone furthest from the root of the joint
-    /// is a [`BoneKind::Jointed`] bone.
-    pub fn set_angle(&mut self, angle: Rotation) {
-        self.angle = angle;
-    }
-
-    /// Returns the rotation of this joint.
-    #[must_use]
-    pub const fn angle(&self) -> Rotation {
-        self.angle
-    }
+    let (mut skeleton, body) = Body::build();
+    assert_eq!(skeleton.find_bone_by_label("r_leg"), Some(body.r_leg));
+    assert_eq!(skeleton.find_joint_by_label("r_knee"), Some(body.r_knee));
+    assert_eq!(skeleton.find_joint_by_label("neck"), Some(body.neck));
+    assert_eq!(
+        skeleton.find_joint_by_label("r_hip"),
+        skeleton.hierarchy().parent_joint(body.r_hip)
+    );
+    assert_eq!(
+        skeleton[body.r_knee].other_axis(body.r_leg.axis_a()),
+        body.r_hip.axis_b()
+    );
+    assert_eq!(
+        skeleton.hierarchy().parent_joint(body.head),
+        Some(body.neck)
+    );
+    skeleton.solve();
+    assert_eq!(skeleton[body.head].start(), skeleton[body.spine].end());
 }
 
-/// The unique ID of a [`Bone`] in a [`Skeleton`].
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
-#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
-pub struct BoneId(u16);
+#[test]
+fn pole() {
+    let mut skeleton = Skeleton::default();
+    let root = skeleton.push_bone(BoneKind::Rigid { length: 1. });
+    let arm = skeleton.push_bone(BoneKind::Jointed {
+        start_length: 1.,
+        end_length: 1.,
+        inverse: false,
+    });
+    skeleton.push_joint(Joint::new(Rotation::default(), root.axis_b(), arm.axis_a()));
+    skeleton[arm].set_desired_end(Some(Vector::new(1., Rotation::default())));
 
-impl BoneId {
-    /// Returns the first axis of this bone.
-    #[must_use]
-    pub const fn axis_a(self) -> BoneAxis {
-        BoneAxis {
-            bone: self,
-            end: BoneEnd::A,
-        }
-    }
+    for inverse in [false, true] {
+        skeleton[arm].kind_mut().set_inverse(inverse);
+        skeleton[arm].set_pole(Some(Vector::new(1., Rotation::degrees(90.))));
+        skeleton.solve();
+        assert!(skeleton[arm].solved_joint().expect("jointed").y > 0.);
 
-    /// Returns the second axis of this bone.
-    #[must_use]
-    pub const fn axis_b(self) -> BoneAxis {
-        BoneAxis {
-            bone: self,
-            end: BoneEnd::B,
-        }
+        skeleton[arm].set_pole(Some(Vector::new(1., Rotation::degrees(-90.))));
+        skeleton.solve();
+        assert!(skeleton[arm].solved_joint().expect("jointed").y < 0.);
     }
 
-    /// Returns the index of this bone within the skeleton.
-    #[must_use]
-    pub fn index(self) -> usize {
-        usize::from(self.0)
-    }
+    // Without a pole, only the skeleton can provide the solved direction.
+    skeleton[arm].set_pole(None);
+    skeleton.solve();
+    let property = animation::BoneProperty::Pole;
+    assert_eq!(
+        property.get(&skeleton[arm]),
+        animation::Value::Vector(Vector::default())
+    );
+    let animation::Value::Vector(solved) = property.get_in_skeleton(&skeleton[arm], &skeleton)
+    else {
+        unreachable!("poles are vectors")
+    };
+    assert!(solved.magnitude > 0.);
 }
 
-/// The unique ID of a [`Joint`] in a [`Skeleton`].
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
-#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
-pub struct JointId(u16);
+#[test]
+fn soft_ik() {
+    let mut skeleton = Skeleton::default();
+    let leg = skeleton.push_bone(BoneKind::Jointed {
+        start_length: 1.,
+        end_length: 1.,
+        inverse: false,
+    });
+    skeleton[leg].set_desired_end(Some(Vector::new(3., Rotation::default())));
+    skeleton.solve();
+    assert!((skeleton[leg].end().x - 2.).abs() < 0.0001);
 
-impl JointId {
-    /// Returns the index of this joint within the skeleton.
-    #[must_use]
-    pub fn index(self) -> usize {
-        usize::from(self.0)
-    }
-}
+    skeleton[leg].set_soft_ik(SoftIk::new(0.5));
+    skeleton.solve();
+    let soft_end = skeleton[leg].end().x;
+    assert!(soft_end > 1.5 && soft_end < 2.);
 
-/// A specific end of a [`Bone`].
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
-pub enum BoneEnd {
-    /// The first end of a bone.
-    A,
-    /// The second end of a bone.
-    B,
-}
+    // Within the soft range, the bone should be short of its target but still
+    // moving towards it.
+    skeleton[leg].set_desired_end(Some(Vector::new(1.75, Rotation::default())));
+    skeleton.solve();
+    let partial = skeleton[leg].end().x;
+    assert!(partial > 1.5 && partial < 1.75 && partial < soft_end);
 
-impl BoneEnd {
-    /// Returns the opposite end of `self`.
-    #[must_use]
-    pub const fn inverse(self) -> Self {
-        match self {
-            Self::A => Self::B,
-            Self::B => Self::A,
-        }
-    }
+    skeleton[leg].set_soft_ik(SoftIk::new(0.5).stretching());
+    skeleton[leg].set_desired_end(Some(Vector::new(3., Rotation::default())));
+    skeleton.solve();
+    assert!((skeleton[leg].end().x - 3.).abs() < 0.0001);
 }
 
 #[test]
-#[allow(clippy::cast_possible_truncation)]
-fn rotation() {
-    assert_eq!(
-        (Rotation::degrees(90.) + Rotation::degrees(180.))
-            .clamped()
-            .to_degrees()
-            .round() as i32,
-        270,
-    );
-    assert_eq!(
-        (Rotation::degrees(90.) + Rotation::degrees(-180.))
-            .clamped()
-            .to_degrees()
-            .round() as i32,
-        270,
-    );
+fn solve_report() {
+    let mut skeleton = Skeleton::default();
+    let root = skeleton.push_bone(BoneKind::Rigid { length: 1. });
+    let arm = skeleton.push_bone(BoneKind::Jointed {
+        start_length: 2.,
+        end_length: 1.,
+        inverse: false,
+    });
+    skeleton.push_joint(Joint::new(Rotation::default(), root.axis_b(), arm.axis_a()));
+
+    skeleton[arm].set_desired_end(Some(Vector::new(2., Rotation::default())));
+    let report = skeleton.solve_with_report();
+    assert!(report.all_reached());
+    assert!(report.bone(root).is_none());
+
+    skeleton[arm].set_desired_end(Some(Vector::new(5., Rotation::default())));
+    let report = skeleton.solve_with_report();
+    let arm_report = report.bone(arm).expect("arm aimed");
+    assert_eq!(arm_report.clamped, Some(ReachLimit::Maximum));
+    assert!((arm_report.shortfall() - 2.).abs() < 0.0001);
+
+    skeleton[arm].set_desired_end(Some(Vector::new(0.5, Rotation::default())));
+    let report = skeleton.solve_with_report();
+    assert_eq!(report.bones()[0].clamped, Some(ReachLimit::Minimum));
+    assert!(!report.all_reached());
+
+    skeleton[arm].set_soft_ik(SoftIk::new(1.));
+    skeleton[arm].set_desired_end(Some(Vector::new(2.8, Rotation::default())));
+    let report = skeleton.solve_with_report();
+    assert_eq!(report.bones()[0].clamped, Some(ReachLimit::Soft));
+    assert!(report.bones()[0].shortfall() > 0.1);
+    assert!(!report.all_reached());
+
+    skeleton[arm].set_soft_ik(SoftIk::new(1.).stretching());
+    let report = skeleton.solve_with_report();
+    assert!(report.all_reached());
 }
@@ -0,0 +1,110 @@
+/// Declares a [`Skeleton`](crate::Skeleton) as a tree of labeled bones, along
+/// with a struct containing the ids of each bone and named joint.
+///
+/// The first bone declared is the root bone. Every other bone must name the
+/// axis of a previously declared bone it is connected to using `from
+/// bone.a` or `from bone.b`, followed by the joint's angle in degrees.
+/// Joints can optionally be named using `as`, which adds the joint's id to
+/// the generated struct.
+///
+/// Each bone is labeled using its name. Each joint is labeled using its name,
+/// or the name of the bone it attaches to its parent when it isn't named. This
+/// matches the labels used by the templates in [`rigs`](crate::rigs).
+///
+/// Bones are declared as a flat list that names each bone's parent rather than
+/// as a nested tree like `spine -> (hip -> leg -> foot)`. Every bone needs a
+/// kind and every joint needs an angle, which nest poorly, and a flat list
+/// stays readable as the tree gets deeper. Because parents are referred to by
+/// name rather than by string labels, referring to a bone that has not been
+/// declared is a compile-time error.
+///
+/// ```rust
+/// funnybones::skeleton! {
+///     /// The ids of a simple leg.
+///     pub struct Leg {
+///         hip: Rigid { length: 0.5 },
+///         leg: Jointed { start_length: 1.5, end_length: 1.5, inverse: true }
+///             from hip.b at -90.,
+///         foot: Rigid { length: 0.5 } from leg.b as ankle at 0.,
+///     }
+/// }
+///
+/// let (skeleton, leg) = Leg::build();
+/// assert_eq!(skeleton.find_bone_by_label("foot"), Some(leg.foot));
+/// assert_eq!(skeleton.find_joint_by_label("ankle"), Some(leg.ankle));
+/// assert_eq!(
+///     skeleton[leg.ankle].other_axis(leg.leg.axis_b()),
+///     leg.foot.axis_a()
+/// );
+/// ```
+#[macro_export]
+macro_rules! skeleton {
+    (@axis $bone:ident a) => {
+        $bone.axis_a()
+    };
+    (@axis $bone:ident b) => {
+        $bone.axis_b()
+    };
+    (@label $bone:ident) => {
+        stringify!($bone)
+    };
+    (@label $bone:ident $joint:ident) => {
+        stringify!($joint)
+    };
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $(
+                $bone:ident : $kind:ident { $($field:ident : $value:expr),* $(,)? }
+                $(from $parent:ident . $end:ident $(as $joint:ident)? at $angle:expr)?
+            ),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        $vis struct $name {
+            $(
+                #[doc = concat!("The `", stringify!($bone), "` bone.")]
+                pub $bone: $crate::BoneId,
+            )+
+            $($($(
+                #[doc = concat!("The `", stringify!($joint), "` joint.")]
+                pub $joint: $crate::JointId,
+            )?)?)+
+        }
+
+        impl $name {
+            /// Returns a new skeleton containing the declared bones and
+            /// joints, along with their ids.
+            #[must_use]
+            #[allow(unused_variables)]
+            pub fn build() -> ($crate::Skeleton, Self) {
+                let mut skeleton = $crate::Skeleton::default();
+                $(
+                    let $bone = skeleton.push_bone(
+                        $crate::BoneKind::$kind { $($field: $value),* }
+                            .with_label(stringify!($bone)),
+                    );
+                    $(
+                        let joint = skeleton.push_joint(
+                            $crate::Joint::new(
+                                $crate::Rotation::degrees($angle),
+                                $crate::skeleton!(@axis $parent $end),
+                                $bone.axis_a(),
+                            )
+                            .with_label($crate::skeleton!(@label $bone $($joint)?)),
+                        );
+                        $(let $joint = joint;)?
+                    )?
+                )+
+                (
+                    skeleton,
+                    Self {
+                        $($bone,)+
+                        $($($($joint,)?)?)+
+                    },
+                )
+            }
+        }
+    };
+}
@@ -0,0 +1,534 @@
+//! Parameterized skeletons for common body plans.
+//!
+//! Each template labels its bones consistently, so animations that refer to
+//! bones by label work on any skeleton built from the same template. Bones on
+//! the left and right side of a body use the `l_` and `r_` prefixes. Each
+//! joint is labeled with the label of the bone it attaches to its parent,
+//! which allows looking up the joint using
+//! [`Skeleton::find_joint_by_label`]. This is the same convention
+//! [`skeleton!`](crate::skeleton) uses for joints that aren't named.
+
+use alloc::{format, vec::Vec};
+
+use crate::{
+    generic::{BoneKind, Joint, Rotation, Skeleton},
+    BoneAxis, BoneId, Scalar,
+};
+
+/// The bone lengths of a [`Humanoid`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HumanoidProportions<T> {
+    /// The length of the spine.
+    pub spine: T,
+    /// The length of the head.
+    pub head: T,
+    /// The distance between the spine and each leg.
+    pub hip: T,
+    /// The length of each thigh.
+    pub upper_leg: T,
+    /// The length of each shin.
+    pub lower_leg: T,
+    /// The length of each foot.
+    pub foot: T,
+    /// The distance between the spine and each arm.
+    pub shoulder: T,
+    /// The length of each upper arm.
+    pub upper_arm: T,
+    /// The length of each forearm.
+    pub lower_arm: T,
+    /// The length of each hand.
+    pub hand: T,
+}
+
+impl<T> Default for HumanoidProportions<T>
+where
+    T: Scalar,
+{
+    fn default() -> Self {
+        Self {
+            spine: T::from_f32(3.),
+            head: T::from_f32(0.5),
+            hip: T::from_f32(0.5),
+            upper_leg: T::from_f32(1.5),
+            lower_leg: T::from_f32(1.5),
+            foot: T::from_f32(0.5),
+            shoulder: T::from_f32(0.5),
+            upper_arm: T::from_f32(1.),
+            lower_arm: T::from_f32(1.),
+            hand: T::from_f32(0.3),
+        }
+    }
+}
+
+/// The bones of a two-legged, two-armed skeleton.
+///
+/// The spine is the root bone, with the legs attached to its start and the
+/// arms and head attached to its end. The skeleton is rotated by -90°, so the
+/// spine points up from the hips.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub struct Humanoid {
+    pub spine: BoneId,
+    pub head: BoneId,
+    pub r_hip: BoneId,
+    pub r_leg: BoneId,
+    pub r_foot: BoneId,
+    pub l_hip: BoneId,
+    pub l_leg: BoneId,
+    pub l_foot: BoneId,
+    pub r_shoulder: BoneId,
+    pub r_arm: BoneId,
+    pub r_hand: BoneId,
+    pub l_shoulder: BoneId,
+    pub l_arm: BoneId,
+    pub l_hand: BoneId,
+}
+
+impl Humanoid {
+    /// Returns a new humanoid skeleton with the given proportions, along with
+    /// the ids of its bones.
+    #[must_use]
+    pub fn build<T: Scalar>(proportions: &HumanoidProportions<T>) -> (Skeleton<T>, Self) {
+        let mut rig = RigBuilder::default();
+        let spine = rig.root("spine", rigid(proportions.spine));
+        let r_hip = rig.attach("r_hip", rigid(proportions.hip), spine.axis_a(), 90.);
+        let r_leg = rig.attach(
+            "r_leg",
+            jointed(proportions.upper_leg, proportions.lower_leg, true),
+            r_hip.axis_b(),
+            -90.,
+        );
+        let r_foot = rig.attach("r_foot", rigid(proportions.foot), r_leg.axis_b(), 0.);
+        let l_hip = rig.attach("l_hip", rigid(proportions.hip), spine.axis_a(), -90.);
+        let l_leg = rig.attach(
+            "l_leg",
+            jointed(proportions.upper_leg, proportions.lower_leg, false),
+            l_hip.axis_b(),
+            90.,
+        );
+        let l_foot = rig.attach("l_foot", rigid(proportions.foot), l_leg.axis_b(), 0.);
+        let r_shoulder = rig.attach(
+            "r_shoulder",
+            rigid(proportions.shoulder),
+            spine.axis_b(),
+            -90.,
+        );
+        let r_arm = rig.attach(
+            "r_arm",
+            jointed(proportions.upper_arm, proportions.lower_arm, true),
+            r_shoulder.axis_b(),
+            0.,
+        );
+        let r_hand = rig.attach("r_hand", rigid(proportions.hand), r_arm.axis_b(), 0.);
+        let l_shoulder = rig.attach(
+            "l_shoulder",
+            rigid(proportions.shoulder),
+            spine.axis_b(),
+            90.,
+        );
+        let l_arm = rig.attach(
+            "l_arm",
+            jointed(proportions.upper_arm, proportions.lower_arm, false),
+            l_shoulder.axis_b(),
+            0.,
+        );
+        let l_hand = rig.attach("l_hand", rigid(proportions.hand), l_arm.axis_b(), 0.);
+        let head = rig.attach("head", rigid(proportions.head), spine.axis_b(), 0.);
+        rig.skeleton
+            .set_rotation(Rotation::degrees(T::from_f32(-90.)));
+
+        (
+            rig.skeleton,
+            Self {
+                spine,
+                head,
+                r_hip,
+                r_leg,
+                r_foot,
+                l_hip,
+                l_leg,
+                l_foot,
+                r_shoulder,
+                r_arm,
+                r_hand,
+                l_shoulder,
+                l_arm,
+                l_hand,
+            },
+        )
+    }
+}
+
+/// The bone lengths of a [`Quadruped`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuadrupedProportions<T> {
+    /// The length of the spine.
+    pub spine: T,
+    /// The length of the neck.
+    pub neck: T,
+    /// The length of the head.
+    pub head: T,
+    /// The length of the tail.
+    pub tail: T,
+    /// The length of the upper half of each leg.
+    pub upper_leg: T,
+    /// The length of the lower half of each leg.
+    pub lower_leg: T,
+    /// The length of each foot.
+    pub foot: T,
+}
+
+impl<T> Default for QuadrupedProportions<T>
+where
+    T: Scalar,
+{
+    fn default() -> Self {
+        Self {
+            spine: T::from_f32(3.),
+            neck: T::from_f32(1.),
+            head: T::from_f32(0.75),
+            tail: T::from_f32(1.5),
+            upper_leg: T::from_f32(1.),
+            lower_leg: T::from_f32(1.),
+            foot: T::from_f32(0.3),
+        }
+    }
+}
+
+/// The bones of a four-legged skeleton.
+///
+/// The spine is the root bone, with the hind legs and tail attached to its
+/// start and the front legs and neck attached to its end.
+///
+/// The skeleton is a side view, so the left and right legs are built
+/// identically and overlap until they are posed differently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub struct Quadruped {
+    pub spine: BoneId,
+    pub neck: BoneId,
+    pub head: BoneId,
+    pub tail: BoneId,
+    pub r_front_leg: BoneId,
+    pub r_front_foot: BoneId,
+    pub l_front_leg: BoneId,
+    pub l_front_foot: BoneId,
+    pub r_hind_leg: BoneId,
+    pub r_hind_foot: BoneId,
+    pub l_hind_leg: BoneId,
+    pub l_hind_foot: BoneId,
+}
+
+impl Quadruped {
+    /// Returns a new quadruped skeleton with the given proportions, along with
+    /// the ids of its bones.
+    #[must_use]
+    pub fn build<T: Scalar>(proportions: &QuadrupedProportions<T>) -> (Skeleton<T>, Self) {
+        let mut rig = RigBuilder::default();
+        let spine = rig.root("spine", rigid(proportions.spine));
+        let neck = rig.attach("neck", rigid(proportions.neck), spine.axis_b(), -45.);
+        let head = rig.attach("head", rigid(proportions.head), neck.axis_b(), 90.);
+        let tail = rig.attach("tail", rigid(proportions.tail), spine.axis_a(), 30.);
+        let leg = jointed(proportions.upper_leg, proportions.lower_leg, false);
+        let r_front_leg = rig.attach("r_front_leg", leg, spine.axis_b(), 90.);
+        let r_front_foot = rig.attach(
+            "r_front_foot",
+            rigid(proportions.foot),
+            r_front_leg.axis_b(),
+            -90.,
+        );
+        let l_front_leg = rig.attach("l_front_leg", leg, spine.axis_b(), 90.);
+        let l_front_foot = rig.attach(
+            "l_front_foot",
+            rigid(proportions.foot),
+            l_front_leg.axis_b(),
+            -90.,
+        );
+        let leg = jointed(proportions.upper_leg, proportions.lower_leg, true);
+        let r_hind_leg = rig.attach("r_hind_leg", leg, spine.axis_a(), -90.);
+        let r_hind_foot = rig.attach(
+            "r_hind_foot",
+            rigid(proportions.foot),
+            r_hind_leg.axis_b(),
+            -90.,
+        );
+        let l_hind_leg = rig.attach("l_hind_leg", leg, spine.axis_a(), -90.);
+        let l_hind_foot = rig.attach(
+            "l_hind_foot",
+            rigid(proportions.foot),
+            l_hind_leg.axis_b(),
+            -90.,
+        );
+
+        (
+            rig.skeleton,
+            Self {
+                spine,
+                neck,
+                head,
+                tail,
+                r_front_leg,
+                r_front_foot,
+                l_front_leg,
+                l_front_foot,
+                r_hind_leg,
+                r_hind_foot,
+                l_hind_leg,
+                l_hind_foot,
+            },
+        )
+    }
+}
+
+/// The bone lengths of a [`Bird`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BirdProportions<T> {
+    /// The length of the body.
+    pub body: T,
+    /// The length of the neck.
+    pub neck: T,
+    /// The length of the head.
+    pub head: T,
+    /// The length of the tail.
+    pub tail: T,
+    /// The length of the inner half of each wing.
+    pub upper_wing: T,
+    /// The length of the outer half of each wing.
+    pub lower_wing: T,
+    /// The length of the upper half of each leg.
+    pub upper_leg: T,
+    /// The length of the lower half of each leg.
+    pub lower_leg: T,
+    /// The length of each foot.
+    pub foot: T,
+}
+
+impl<T> Default for BirdProportions<T>
+where
+    T: Scalar,
+{
+    fn default() -> Self {
+        Self {
+            body: T::from_f32(1.5),
+            neck: T::from_f32(0.75),
+            head: T::from_f32(0.5),
+            tail: T::from_f32(0.75),
+            upper_wing: T::from_f32(1.),
+            lower_wing: T::from_f32(1.),
+            upper_leg: T::from_f32(0.5),
+            lower_leg: T::from_f32(0.5),
+            foot: T::from_f32(0.25),
+        }
+    }
+}
+
+/// The bones of a two-legged, two-winged skeleton.
+///
+/// The body is the root bone, with the legs and tail attached to its start
+/// and the wings and neck attached to its end.
+///
+/// The skeleton is a side view, so the left and right wings, as well as the
+/// left and right legs, are built identically and overlap until they are
+/// posed differently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub struct Bird {
+    pub body: BoneId,
+    pub neck: BoneId,
+    pub head: BoneId,
+    pub tail: BoneId,
+    pub r_wing: BoneId,
+    pub l_wing: BoneId,
+    pub r_leg: BoneId,
+    pub r_foot: BoneId,
+    pub l_leg: BoneId,
+    pub l_foot: BoneId,
+}
+
+impl Bird {
+    /// Returns a new bird skeleton with the given proportions, along with the
+    /// ids of its bones.
+    #[must_use]
+    pub fn build<T: Scalar>(proportions: &BirdProportions<T>) -> (Skeleton<T>, Self) {
+        let mut rig = RigBuilder::default();
+        let body = rig.root("body", rigid(proportions.body));
+        let neck = rig.attach("neck", rigid(proportions.neck), body.axis_b(), -60.);
+        let head = rig.attach("head", rigid(proportions.head), neck.axis_b(), 60.);
+        let tail = rig.attach("tail", rigid(proportions.tail), body.axis_a(), 15.);
+        let r_wing = rig.attach(
+            "r_wing",
+            jointed(proportions.upper_wing, proportions.lower_wing, true),
+            body.axis_b(),
+            -135.,
+        );
+        let l_wing = rig.attach(
+            "l_wing",
+            jointed(proportions.upper_wing, proportions.lower_wing, true),
+            body.axis_b(),
+            -135.,
+        );
+        let leg = jointed(proportions.upper_leg, proportions.lower_leg, true);
+        let r_leg = rig.attach("r_leg", leg, body.axis_a(), -90.);
+        let r_foot = rig.attach("r_foot", rigid(proportions.foot), r_leg.axis_b(), 90.);
+        let l_leg = rig.attach("l_leg", leg, body.axis_a(), -90.);
+        let l_foot = rig.attach("l_foot", rigid(proportions.foot), l_leg.axis_b(), 90.);
+
+        (
+            rig.skeleton,
+            Self {
+                body,
+                neck,
+                head,
+                tail,
+                r_wing,
+                l_wing,
+                r_leg,
+                r_foot,
+                l_leg,
+                l_foot,
+            },
+        )
+    }
+}
+
+/// The bone lengths of a [`Snake`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SnakeProportions<T> {
+    /// The length of the head.
+    pub head: T,
+    /// The number of bones making up the body.
+    pub segments: usize,
+    /// The length of each body segment.
+    pub segment: T,
+}
+
+impl<T> Default for SnakeProportions<T>
+where
+    T: Scalar,
+{
+    fn default() -> Self {
+        Self {
+            head: T::from_f32(0.5),
+            segments: 8,
+            segment: T::from_f32(0.75),
+        }
+    }
+}
+
+/// The bones of a limbless skeleton.
+///
+/// The head is the root bone, with the body segments trailing from its start.
+/// Each segment is labeled `segment_N`, where `N` counts up from `0` starting
+/// closest to the head.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snake {
+    /// The head of the snake.
+    pub head: BoneId,
+    /// The body segments, ordered from the head to the tip of the tail.
+    pub segments: Vec<BoneId>,
+}
+
+impl Snake {
+    /// Returns a new snake skeleton with the given proportions, along with the
+    /// ids of its bones.
+    #[must_use]
+    pub fn build<T: Scalar>(proportions: &SnakeProportions<T>) -> (Skeleton<T>, Self) {
+        let mut rig = RigBuilder::default();
+        let head = rig.root("head", rigid(proportions.head));
+        let mut segments = Vec::with_capacity(proportions.segments);
+        let mut parent = head.axis_a();
+        for index in 0..proportions.segments {
+            let segment = rig.attach(
+                &format!("segment_{index}"),
+                rigid(proportions.segment),
+                parent,
+                0.,
+            );
+            segments.push(segment);
+            parent = segment.axis_b();
+        }
+
+        (rig.skeleton, Self { head, segments })
+    }
+}
+
+#[derive(Default)]
+struct RigBuilder<T: Scalar> {
+    skeleton: Skeleton<T>,
+}
+
+impl<T: Scalar> RigBuilder<T> {
+    fn root(&mut self, label: &str, kind: BoneKind<T>) -> BoneId {
+        self.skeleton.push_bone(kind.with_label(label))
+    }
+
+    fn attach(&mut self, label: &str, kind: BoneKind<T>, parent: BoneAxis, degrees: f32) -> BoneId {
+        let bone = self.skeleton.push_bone(kind.with_label(label));
+        self.skeleton.push_joint(
+            Joint::new(
+                Rotation::degrees(T::from_f32(degrees)),
+                parent,
+                bone.axis_a(),
+            )
+            .with_label(label),
+        );
+        bone
+    }
+}
+
+fn rigid<T: Scalar>(length: T) -> BoneKind<T> {
+    BoneKind::Rigid { length }
+}
+
+fn jointed<T: Scalar>(start_length: T, end_length: T, inverse: bool) -> BoneKind<T> {
+    BoneKind::Jointed {
+        start_length,
+        end_length,
+        inverse,
+    }
+}
+
+#[test]
+fn templates() {
+    let (mut skeleton, humanoid) = Humanoid::build(&HumanoidProportions::<f32>::default());
+    assert_eq!(skeleton.find_bone_by_label("r_foot"), Some(humanoid.r_foot));
+    assert_eq!(
+        skeleton.hierarchy().parent_joint(humanoid.l_hand),
+        skeleton.find_joint_by_label("l_hand")
+    );
+    skeleton.solve();
+    assert!((skeleton[humanoid.r_leg].kind().full_length() - 3.).abs() < f32::EPSILON);
+    // With the default coordinate system, positive y points down.
+    assert!(skeleton[humanoid.spine].end().y < skeleton[humanoid.spine].start().y);
+    assert!(skeleton[humanoid.r_foot].start().y > skeleton[humanoid.spine].start().y);
+
+    let (mut skeleton, quadruped) = Quadruped::build(&QuadrupedProportions::<f32>::default());
+    skeleton.solve();
+    for foot in [
+        quadruped.r_front_foot,
+        quadruped.l_front_foot,
+        quadruped.r_hind_foot,
+        quadruped.l_hind_foot,
+    ] {
+        assert!(skeleton[foot].start().y > 1.);
+    }
+    assert!(skeleton[quadruped.head].start().y < 0.);
+    assert!(skeleton[quadruped.tail].end().y < 0.);
+
+    let (mut skeleton, bird) = Bird::build(&BirdProportions::<f32>::default());
+    skeleton.solve();
+    assert!(skeleton[bird.r_foot].start().y > 0.);
+    assert!(skeleton[bird.r_wing].end().y < 0.);
+
+    let proportions = SnakeProportions::<f32> {
+        segments: 4,
+        ..SnakeProportions::default()
+    };
+    let (mut skeleton, snake) = Snake::build(&proportions);
+    skeleton.solve();
+    assert_eq!(
+        skeleton.find_bone_by_label("segment_3"),
+        Some(snake.segments[3])
+    );
+    assert!((skeleton[snake.segments[3]].end().x + 3.).abs() < 0.001);
+}
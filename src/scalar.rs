@@ -0,0 +1,145 @@
+use core::{
+    fmt::{Debug, Display},
+    ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign},
+};
+
+/// A number type that skeletons can be solved with.
+///
+/// This trait is implemented for `f32`, `f64`, and [`Fixed`](crate::Fixed).
+pub trait Scalar:
+    Copy
+    + Default
+    + Debug
+    + Display
+    + PartialEq
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+    + Send
+    + Sync
+    + 'static
+{
+    /// The value `0`.
+    const ZERO: Self;
+    /// The value `1`.
+    const ONE: Self;
+    /// Archimedes' constant (π).
+    const PI: Self;
+    /// The difference between `1.0` and the next larger representable number.
+    const EPSILON: Self;
+
+    /// Returns the closest representable value to `value`.
+    fn from_f32(value: f32) -> Self;
+    /// Returns the absolute value of `self`.
+    #[must_use]
+    fn abs(self) -> Self;
+    /// Returns the square root of `self`.
+    #[must_use]
+    fn sqrt(self) -> Self;
+    /// Returns the sine of `self`, in radians.
+    #[must_use]
+    fn sin(self) -> Self;
+    /// Returns the cosine of `self`, in radians.
+    #[must_use]
+    fn cos(self) -> Self;
+    /// Returns the arccosine of `self`, in radians.
+    #[must_use]
+    fn acos(self) -> Self;
+    /// Returns the four quadrant arctangent of `self` (y) and `x`, in
+    /// radians.
+    #[must_use]
+    fn atan2(self, x: Self) -> Self;
+    /// Returns `e^(self)`.
+    #[must_use]
+    fn exp(self) -> Self;
+
+    /// Returns the smaller of `self` and `other`.
+    #[must_use]
+    fn min(self, other: Self) -> Self {
+        if other < self {
+            other
+        } else {
+            self
+        }
+    }
+
+    /// Returns the larger of `self` and `other`.
+    #[must_use]
+    fn max(self, other: Self) -> Self {
+        if other > self {
+            other
+        } else {
+            self
+        }
+    }
+
+    /// Returns `self` restricted to the range `min..=max`.
+    #[must_use]
+    fn clamp(self, min: Self, max: Self) -> Self {
+        self.max(min).min(max)
+    }
+}
+
+macro_rules! impl_float_scalar {
+    ($type:ident, $abs:ident, $sqrt:ident, $sin:ident, $cos:ident, $acos:ident, $atan2:ident, $exp:ident) => {
+        impl Scalar for $type {
+            const ZERO: Self = 0.;
+            const ONE: Self = 1.;
+            const PI: Self = core::$type::consts::PI;
+            const EPSILON: Self = $type::EPSILON;
+
+            fn from_f32(value: f32) -> Self {
+                Self::from(value)
+            }
+
+            float_fn!($type, abs, $abs);
+            float_fn!($type, sqrt, $sqrt);
+            float_fn!($type, sin, $sin);
+            float_fn!($type, cos, $cos);
+            float_fn!($type, acos, $acos);
+            float_fn!($type, exp, $exp);
+
+            #[cfg(feature = "std")]
+            fn atan2(self, x: Self) -> Self {
+                $type::atan2(self, x)
+            }
+
+            #[cfg(not(feature = "std"))]
+            fn atan2(self, x: Self) -> Self {
+                libm::$atan2(self, x)
+            }
+
+            fn min(self, other: Self) -> Self {
+                $type::min(self, other)
+            }
+
+            fn max(self, other: Self) -> Self {
+                $type::max(self, other)
+            }
+        }
+    };
+}
+
+/// Implements a single argument float function using `std` when available,
+/// falling back to `libm`.
+macro_rules! float_fn {
+    ($type:ident, $name:ident, $libm:ident) => {
+        #[cfg(feature = "std")]
+        fn $name(self) -> Self {
+            $type::$name(self)
+        }
+
+        #[cfg(not(feature = "std"))]
+        fn $name(self) -> Self {
+            libm::$libm(self)
+        }
+    };
+}
+
+impl_float_scalar!(f32, fabsf, sqrtf, sinf, cosf, acosf, atan2f, expf);
+impl_float_scalar!(f64, fabs, sqrt, sin, cos, acos, atan2, exp);
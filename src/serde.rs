@@ -1,4 +1,4 @@
-use std::borrow::Cow;
+use alloc::{borrow::Cow, format, string::String, vec::Vec};
 
 use serde::{
     de::{self, Visitor},
@@ -6,43 +6,89 @@ use serde::{
     Deserialize, Serialize,
 };
 
-use crate::{Rotation, Bone, BoneAxis, BoneKind, Joint, Skeleton, Vector};
+use crate::{
+    generic::{Bone, BoneKind, Joint, Rotation, Skeleton, Socket, SoftIk, Vector},
+    BoneAxis, BoneId, BoneSegment, CoordinateSystem, Scalar,
+};
 
-impl Serialize for Skeleton {
+impl<T> Serialize for Skeleton<T>
+where
+    T: Scalar + Serialize,
+{
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        let mut s = serializer.serialize_struct("Skeleton", 2)?;
+        let field_count = 2
+            + usize::from(!self.sockets.is_empty())
+            + usize::from(self.scale != T::ONE)
+            + usize::from(self.flip_horizontal)
+            + usize::from(self.flip_vertical)
+            + usize::from(self.coordinate_system != CoordinateSystem::default());
+        let mut s = serializer.serialize_struct("Skeleton", field_count)?;
         s.serialize_field("bones", &self.bones)?;
         s.serialize_field("joints", &self.joints)?;
+        if !self.sockets.is_empty() {
+            s.serialize_field("sockets", &self.sockets)?;
+        }
+        if self.scale != T::ONE {
+            s.serialize_field("scale", &self.scale)?;
+        }
+        if self.flip_horizontal {
+            s.serialize_field("flip_horizontal", &true)?;
+        }
+        if self.flip_vertical {
+            s.serialize_field("flip_vertical", &true)?;
+        }
+        if self.coordinate_system != CoordinateSystem::default() {
+            s.serialize_field("coordinate_system", &self.coordinate_system)?;
+        }
         s.end()
     }
 }
 
-impl<'de> Deserialize<'de> for Skeleton {
+impl<'de, T> Deserialize<'de> for Skeleton<T>
+where
+    T: Scalar + Deserialize<'de>,
+{
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
         deserializer.deserialize_struct(
             "Skeleton",
-            &["bones", "joints"],
+            &[
+                "bones",
+                "joints",
+                "sockets",
+                "scale",
+                "flip_horizontal",
+                "flip_vertical",
+                "coordinate_system",
+            ],
             SkeletonVisitor::default(),
         )
     }
 }
 
 #[derive(Default)]
-struct SkeletonVisitor {
-    bones: Vec<DeserializedBone>,
-    joints: Vec<DeserializedJoint>,
+struct SkeletonVisitor<T: Scalar> {
+    bones: Vec<DeserializedBone<T>>,
+    joints: Vec<DeserializedJoint<T>>,
+    sockets: Vec<DeserializedSocket<T>>,
+    scale: Option<T>,
+    flip_horizontal: bool,
+    flip_vertical: bool,
+    coordinate_system: CoordinateSystem,
 }
 
-impl<'de> Visitor<'de> for SkeletonVisitor {
-    type Value = Skeleton;
+impl<'de, T> Visitor<'de> for SkeletonVisitor<T>
+where
+    T: Scalar + Deserialize<'de>,
+{
+    type Value = Skeleton<T>;
 
-    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+    fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
         write!(formatter, "a Skeleton")
     }
 
@@ -58,6 +104,21 @@ impl<'de> Visitor<'de> for SkeletonVisitor {
                 "joints" => {
                     self.joints = map.next_value()?;
                 }
+                "sockets" => {
+                    self.sockets = map.next_value()?;
+                }
+                "scale" => {
+                    self.scale = Some(map.next_value()?);
+                }
+                "flip_horizontal" => {
+                    self.flip_horizontal = map.next_value()?;
+                }
+                "flip_vertical" => {
+                    self.flip_vertical = map.next_value()?;
+                }
+                "coordinate_system" => {
+                    self.coordinate_system = map.next_value()?;
+                }
                 _ => {
                     return Err(<A::Error as de::Error>::custom(format!(
                         "unexpected field {key}"
@@ -66,28 +127,47 @@ impl<'de> Visitor<'de> for SkeletonVisitor {
             }
         }
 
-        let mut skeleton = Skeleton::default();
+        let mut skeleton = Skeleton::<T>::default();
+        skeleton.set_scale(self.scale.unwrap_or(T::ONE));
+        skeleton.set_flip_horizontal(self.flip_horizontal);
+        skeleton.set_flip_vertical(self.flip_vertical);
+        skeleton.set_coordinate_system(self.coordinate_system);
         for bone in self.bones.drain(..) {
             let id = skeleton.push_bone(bone.kind.with_label(bone.label));
             if let Some(target) = bone.target {
                 skeleton[id].set_desired_end(Some(target));
             }
+            skeleton[id].set_pole(bone.pole);
+            skeleton[id].set_soft_ik(bone.soft_ik);
         }
         for joint in self.joints.drain(..) {
             skeleton
                 .push_joint(Joint::new(joint.angle, joint.from, joint.to).with_label(joint.label));
         }
+        for socket in self.sockets.drain(..) {
+            skeleton.push_socket(
+                Socket::new(socket.bone, socket.segment, socket.fraction)
+                    .with_offset(socket.offset)
+                    .with_label(socket.label),
+            );
+        }
         Ok(skeleton)
     }
 }
 
-impl Serialize for Bone {
+impl<T> Serialize for Bone<T>
+where
+    T: Scalar + Serialize,
+{
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        let field_count =
-            1 + usize::from(self.label.is_some()) + usize::from(self.desired_end.is_some());
+        let field_count = 1
+            + usize::from(self.label.is_some())
+            + usize::from(self.desired_end.is_some())
+            + usize::from(self.pole.is_some())
+            + usize::from(self.soft_ik != SoftIk::<T>::default());
         let mut b = serializer.serialize_struct("Bone", field_count)?;
         b.serialize_field("kind", &self.kind)?;
         if let Some(label) = &self.label {
@@ -96,20 +176,33 @@ impl Serialize for Bone {
         if let Some(desired_end) = self.desired_end {
             b.serialize_field("target", &desired_end)?;
         }
+        if let Some(pole) = self.pole {
+            b.serialize_field("pole", &pole)?;
+        }
+        if self.soft_ik != SoftIk::<T>::default() {
+            b.serialize_field("soft_ik", &self.soft_ik)?;
+        }
         b.end()
     }
 }
 
 #[derive(Deserialize)]
-struct DeserializedBone {
+struct DeserializedBone<T: Scalar> {
     #[serde(default)]
     label: String,
-    kind: BoneKind,
+    kind: BoneKind<T>,
     #[serde(default)]
-    target: Option<Vector>,
+    target: Option<Vector<T>>,
+    #[serde(default)]
+    pole: Option<Vector<T>>,
+    #[serde(default)]
+    soft_ik: SoftIk<T>,
 }
 
-impl Serialize for Joint {
+impl<T> Serialize for Joint<T>
+where
+    T: Scalar + Serialize,
+{
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
@@ -127,17 +220,57 @@ impl Serialize for Joint {
 }
 
 #[derive(Deserialize)]
-struct DeserializedJoint {
+struct DeserializedJoint<T: Scalar> {
     from: BoneAxis,
     to: BoneAxis,
-    angle: Rotation,
+    angle: Rotation<T>,
+    #[serde(default)]
+    label: String,
+}
+
+impl<T> Serialize for Socket<T>
+where
+    T: Scalar + Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let field_count =
+            3 + usize::from(self.offset != Vector::default()) + usize::from(self.label.is_some());
+        let mut b = serializer.serialize_struct("Socket", field_count)?;
+        b.serialize_field("bone", &self.bone)?;
+        b.serialize_field("segment", &self.segment)?;
+        b.serialize_field("fraction", &self.fraction)?;
+        if self.offset != Vector::default() {
+            b.serialize_field("offset", &self.offset)?;
+        }
+        if let Some(label) = &self.label {
+            b.serialize_field("label", &**label)?;
+        }
+        b.end()
+    }
+}
+
+#[derive(Deserialize)]
+struct DeserializedSocket<T: Scalar> {
+    bone: BoneId,
+    segment: BoneSegment,
+    fraction: T,
+    #[serde(default)]
+    offset: Vector<T>,
     #[serde(default)]
     label: String,
 }
 
 #[test]
 fn roundtrip() {
+    use crate::{BoneKind, Joint, Rotation, Skeleton, Socket};
+
     let mut s = Skeleton::default();
+    s.set_coordinate_system(CoordinateSystem::Y_UP);
+    s.set_scale(2.);
+    s.set_flip_horizontal(true);
     let spine = s.push_bone(BoneKind::Rigid { length: 1.0 }.with_label("spine"));
     let other = s.push_bone(BoneKind::Jointed {
         start_length: 2.0,
@@ -149,9 +282,25 @@ fn roundtrip() {
         spine.axis_a(),
         other.axis_b(),
     ));
+    let socket = s.push_socket(
+        Socket::new(other, BoneSegment::End, 0.5)
+            .with_offset(Vector::new(0.25, Rotation::degrees(90.)))
+            .with_label("hand"),
+    );
     let serialized = pot::to_vec(&s).unwrap();
     let deserialized: Skeleton = pot::from_slice(&serialized).unwrap();
     assert_eq!(deserialized[spine].label(), "spine");
     assert_eq!(deserialized[other].label(), "");
     assert_eq!(deserialized[joint].angle(), Rotation::radians(0.));
+    assert_eq!(deserialized.coordinate_system(), CoordinateSystem::Y_UP);
+    assert_eq!(deserialized.find_socket_by_label("hand"), Some(socket));
+    assert_eq!(deserialized[socket].bone(), other);
+    assert_eq!(deserialized[socket].segment(), BoneSegment::End);
+    assert_eq!(
+        deserialized[socket].offset(),
+        Vector::new(0.25, Rotation::degrees(90.))
+    );
+    assert!((deserialized.scale() - 2.).abs() < f32::EPSILON);
+    assert!(deserialized.flip_horizontal());
+    assert!(!deserialized.flip_vertical());
 }